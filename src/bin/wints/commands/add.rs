@@ -5,13 +5,15 @@ use wints::ops;
 use wints::ops::wints_add::AddOptions;
 
 use crate::commands::{
-    general_args, get_global_basedir, get_pathbuf_arg, get_string_arg, global_arg, module_arg,
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    get_string_arg, global_arg, module_arg,
 };
 
 pub fn command() -> Command {
     Command::new("add")
         .about("Add a url to a context")
         .args(general_args())
+        .arg(dry_run_format_arg())
         .arg(module_arg())
         .arg(global_arg())
         .arg(
@@ -31,13 +33,14 @@ pub fn command() -> Command {
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let module_name = get_string_arg(args, "module");
     let url = get_string_arg(args, "url");
     let context = get_string_arg(args, "context");
     let global_module = args.get_flag("global");
     let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
 
     ops::wints_add::add(AddOptions {
         local_basedir,
@@ -47,5 +50,6 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
         url,
         context,
         dry_run,
+        dry_run_format,
     })
 }
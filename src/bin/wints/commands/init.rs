@@ -1,37 +1,73 @@
 use anyhow::Result;
-use clap::builder::PossibleValuesParser;
+use clap::ArgAction::{Append, SetTrue};
 use clap::{Arg, ArgMatches, Command};
 
 use wints::ops;
 use wints::ops::wints_init::InitOptions;
 
 use crate::commands::{
-    general_args, get_global_basedir, get_pathbuf_arg, get_string_arg, global_arg, module_arg,
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    get_pathbuf_arg, get_string_arg, global_arg, module_arg,
 };
 
 pub fn command() -> Command {
     Command::new("init")
         .about("Initialise a new module")
         .args(general_args())
+        .arg(dry_run_format_arg())
         .arg(module_arg())
         .arg(global_arg())
         .arg(
             Arg::new("template")
-                .help("Template name to use")
+                .help("Template name to use, 'empty', 'default', 'git' or one of the templates folders")
                 .value_name("TEMPLATE")
-                .value_parser(PossibleValuesParser::new(["empty", "default"]))
                 .default_value("empty")
                 .index(1),
         )
+        .arg(
+            Arg::new("repository")
+                .help("Repository whose remote is used by the 'git' template")
+                .long("repository")
+                .value_name("PATH")
+                .default_value("."),
+        )
+        .arg(
+            Arg::new("var")
+                .help("Value of a template variable, asked when not set")
+                .long("var")
+                .value_name("KEY=VALUE")
+                .action(Append),
+        )
+        .arg(
+            Arg::new("force")
+                .help("Overwrite the module when it already exists")
+                .short('f')
+                .long("force")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("list-templates")
+                .help("List the built-in templates and the ones of the templates folders")
+                .long("list-templates")
+                .action(SetTrue),
+        )
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let module_name = get_string_arg(args, "module");
     let template = get_string_arg(args, "template");
+    let repository = get_pathbuf_arg(args, "repository");
+    let variables = args
+        .get_many::<String>("var")
+        .map(|values| values.cloned().collect())
+        .unwrap_or_default();
+    let list_templates = args.get_flag("list-templates");
+    let force = args.get_flag("force");
     let global_module = args.get_flag("global");
     let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
 
     ops::wints_init::init(InitOptions {
         local_basedir,
@@ -39,6 +75,11 @@ pub fn exec(args: &ArgMatches) -> Result<()> {
         module_name,
         global_module,
         template,
+        repository,
+        variables,
+        list_templates,
+        force,
         dry_run,
+        dry_run_format,
     })
 }
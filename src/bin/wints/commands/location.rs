@@ -0,0 +1,23 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_where::WhereOptions;
+
+use crate::commands::{general_args, get_global_config_dir, get_local_config_dir};
+
+pub fn command() -> Command {
+    Command::new("where")
+        .about("Show the local and global configuration folders in use")
+        .args(general_args())
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let local_config_dir = get_local_config_dir(args);
+    let global_config_dir = get_global_config_dir(args);
+
+    ops::wints_where::where_(WhereOptions {
+        local_config_dir,
+        global_config_dir,
+    })
+}
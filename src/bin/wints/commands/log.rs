@@ -0,0 +1,26 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_log::LogOptions;
+
+use crate::commands::{general_args, get_global_basedir, get_local_basedir, global_arg};
+
+pub fn command() -> Command {
+    Command::new("log")
+        .about("List the commands of the journal and the files they changed")
+        .args(general_args())
+        .arg(global_arg())
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let global = args.get_flag("global");
+
+    ops::wints_log::log(LogOptions {
+        local_basedir,
+        global_basedir,
+        global,
+    })
+}
@@ -0,0 +1,23 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_migrate::MigrateOptions;
+
+use crate::commands::{general_args, get_global_config_dir};
+
+pub fn command() -> Command {
+    Command::new("migrate")
+        .about("Move the legacy HOME_DIR/.wints global configuration to the XDG folders")
+        .args(general_args())
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let global_config_dir = get_global_config_dir(args);
+    let dry_run = args.get_flag("dry-run");
+
+    ops::wints_migrate::migrate(MigrateOptions {
+        global_config_dir,
+        dry_run,
+    })
+}
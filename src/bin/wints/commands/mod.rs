@@ -1,22 +1,42 @@
 use std::path::PathBuf;
 
 use anyhow::Result;
-use clap::ArgAction::SetTrue;
-use clap::{Arg, ArgMatches, Command};
-use directories_next::BaseDirs;
+use clap::ArgAction::{Append, SetTrue};
+use clap::{value_parser, Arg, ArgMatches, Command};
+
+use wints::ops::wints_scan::ScanSelectionOptions;
+use wints::util::config_dirs;
+use wints::util::config_dirs::{GlobalConfigDir, LocalConfigDir};
+use wints::util::file_changes::DryRunFormat;
+use wints::util::scan_selection::parse_filesize;
+use wints::util::source_comments::COMMENT_EXTENSIONS;
 
 mod add;
 mod init;
+mod location;
+mod log;
+mod migrate;
+mod prune;
+mod redo;
 mod scan;
 mod search;
+mod undo;
 mod url;
+mod which;
 
 pub fn builtin() -> Vec<Command> {
     vec![
         init::command(),
         add::command(),
         scan::command(),
+        prune::command(),
         url::command(),
+        which::command(),
+        undo::command(),
+        redo::command(),
+        log::command(),
+        location::command(),
+        migrate::command(),
     ]
 }
 
@@ -30,13 +50,12 @@ pub fn global_args() -> Vec<Arg> {
 pub fn general_args() -> Vec<Arg> {
     vec![
         Arg::new("config")
-            .help("Folder of local configuration storage")
+            .help("Folder of local configuration storage [default: WINTS_DIR, or the nearest .wints from the current directory]")
             .value_name("PATH")
-            .default_value(".wints")
             .short('C')
             .long("config"),
         Arg::new("global-config")
-            .help("Folder of global configuration storage [default: HOME_DIR/.wints]")
+            .help("Folder of global configuration storage [default: WINTS_GLOBAL_DIR, or XDG_CONFIG_HOME/wints]")
             .value_name("PATH")
             .short('G')
             .long("global-config"),
@@ -65,12 +84,75 @@ pub fn global_arg() -> Arg {
         .action(SetTrue)
 }
 
+pub fn dry_run_format_arg() -> Arg {
+    Arg::new("json")
+        .help("With --dry-run, print the changes as one JSON document instead of diffs")
+        .long("json")
+        .requires("dry-run")
+        .action(SetTrue)
+}
+
+// File selection of scan, also used by prune to find the same files
+pub fn scan_selection_args() -> Vec<Arg> {
+    vec![
+        Arg::new("type")
+            .help("Only scan files of this type, like 'md' (see 'rg --type-list')")
+            .long("type")
+            .value_name("TYPE")
+            .action(Append),
+        Arg::new("type-not")
+            .help("Do not scan files of this type, like 'lock'")
+            .long("type-not")
+            .value_name("TYPE")
+            .action(Append),
+        Arg::new("max-depth")
+            .help("Descend at most this number of directories")
+            .long("max-depth")
+            .value_name("NUM")
+            .value_parser(value_parser!(usize)),
+        Arg::new("max-filesize")
+            .help("Skip files larger than this size, like '100K' or '1M'")
+            .long("max-filesize")
+            .value_name("SIZE")
+            .value_parser(|size: &str| parse_filesize(size).map_err(|err| err.to_string())),
+        Arg::new("hidden")
+            .help("Scan hidden files and directories")
+            .long("hidden")
+            .action(SetTrue),
+        Arg::new("follow-symlinks")
+            .help("Follow symbolic links")
+            .long("follow-symlinks")
+            .action(SetTrue),
+        Arg::new("no-gitignore")
+            .help("Scan the files ignored by .gitignore files")
+            .long("no-gitignore")
+            .action(SetTrue),
+        Arg::new("comments-only")
+            .help("Only scan the comments and docstrings of source files, of all languages or of the given extensions (rs,py,...)")
+            .long("comments-only")
+            .value_name("EXT")
+            .num_args(0..)
+            .value_delimiter(','),
+        Arg::new("site-root")
+            .help("URL of the scanned paths, to resolve the relative links of HTML files")
+            .long("site-root")
+            .value_name("URL"),
+    ]
+}
+
 pub fn builtin_exec(cmd: &str) -> fn(&ArgMatches) -> Result<()> {
     match cmd {
         "init" => init::exec,
         "add" => add::exec,
         "scan" => scan::exec,
+        "prune" => prune::exec,
         "url" => url::exec,
+        "which" => which::exec,
+        "undo" => undo::exec,
+        "redo" => redo::exec,
+        "log" => log::exec,
+        "where" => location::exec,
+        "migrate" => migrate::exec,
         _ => global_exec(),
     }
 }
@@ -90,9 +172,68 @@ fn get_pathbuf_arg(args: &ArgMatches, arg_name: &str) -> PathBuf {
     PathBuf::from(get_string_arg(args, arg_name))
 }
 
-fn get_global_basedir(args: &ArgMatches) -> PathBuf {
-    match args.get_one::<String>("global-config").map(|s| s.as_str()) {
-        None => BaseDirs::new().unwrap().home_dir().join(".wints"),
-        Some(value) => PathBuf::from(value),
+fn get_strings_arg(args: &ArgMatches, arg_name: &str) -> Vec<String> {
+    args.get_many::<String>(arg_name)
+        .unwrap_or_default()
+        .cloned()
+        .collect()
+}
+
+// Unset flags fall back to the options.yaml values
+fn flag_if_set(args: &ArgMatches, arg_name: &str, value: bool) -> Option<bool> {
+    match args.get_flag(arg_name) {
+        true => Some(value),
+        false => None,
     }
 }
+
+fn get_scan_selection_options(args: &ArgMatches) -> ScanSelectionOptions {
+    let comments_only = match args.contains_id("comments-only") {
+        false => None,
+        true => match get_strings_arg(args, "comments-only") {
+            extensions if extensions.is_empty() => Some(
+                COMMENT_EXTENSIONS
+                    .iter()
+                    .map(|extension| extension.to_string())
+                    .collect(),
+            ),
+            extensions => Some(extensions),
+        },
+    };
+    ScanSelectionOptions {
+        types: get_strings_arg(args, "type"),
+        types_not: get_strings_arg(args, "type-not"),
+        max_depth: args.get_one::<usize>("max-depth").copied(),
+        max_filesize: args.get_one::<u64>("max-filesize").copied(),
+        hidden: flag_if_set(args, "hidden", true),
+        follow_symlinks: flag_if_set(args, "follow-symlinks", true),
+        gitignore: flag_if_set(args, "no-gitignore", false),
+        comments_only,
+        site_root: args.get_one::<String>("site-root").cloned(),
+    }
+}
+
+fn get_dry_run_format(args: &ArgMatches) -> DryRunFormat {
+    match args.get_flag("json") {
+        true => DryRunFormat::Json,
+        false => DryRunFormat::Diff,
+    }
+}
+
+fn get_local_config_dir(args: &ArgMatches) -> LocalConfigDir {
+    let option = args.get_one::<String>("config").map(PathBuf::from);
+    config_dirs::local_config_dir(option, &get_global_basedir(args))
+}
+
+fn get_local_basedir(args: &ArgMatches) -> PathBuf {
+    get_local_config_dir(args).path
+}
+
+fn get_global_config_dir(args: &ArgMatches) -> GlobalConfigDir {
+    let option = args.get_one::<String>("global-config").map(PathBuf::from);
+    config_dirs::global_config_dir(option)
+}
+
+fn get_global_basedir(args: &ArgMatches) -> PathBuf {
+    get_global_config_dir(args).path
+}
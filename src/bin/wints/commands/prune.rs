@@ -0,0 +1,59 @@
+use anyhow::Result;
+use clap::ArgAction::SetTrue;
+use clap::{Arg, ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_prune::PruneOptions;
+
+use crate::commands::{
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    get_pathbuf_arg, get_scan_selection_options, get_string_arg, global_arg, module_arg,
+    scan_selection_args,
+};
+
+pub fn command() -> Command {
+    Command::new("prune")
+        .about("Remove the scanned URLs which are no longer present in a directory tree")
+        .args(general_args())
+        .arg(dry_run_format_arg())
+        .arg(module_arg())
+        .arg(global_arg())
+        .arg(
+            Arg::new("path")
+                .help("Path to scan (file or directories)")
+                .value_name("PATH")
+                .default_value(".")
+                .index(1),
+        )
+        .args(scan_selection_args())
+        .arg(
+            Arg::new("obsolete")
+                .help("Move the stale URLs to the 'obsolete' context instead of removing them")
+                .long("obsolete")
+                .action(SetTrue),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let module_name = get_string_arg(args, "module");
+    let scan_path = get_pathbuf_arg(args, "path");
+    let selection = get_scan_selection_options(args);
+    let global_module = args.get_flag("global");
+    let obsolete = args.get_flag("obsolete");
+    let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
+
+    ops::wints_prune::prune(PruneOptions {
+        local_basedir,
+        global_basedir,
+        module_name,
+        global_module,
+        scan_path,
+        selection,
+        obsolete,
+        dry_run,
+        dry_run_format,
+    })
+}
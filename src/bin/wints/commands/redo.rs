@@ -0,0 +1,34 @@
+use anyhow::Result;
+use clap::{ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_redo::RedoOptions;
+
+use crate::commands::{
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    global_arg,
+};
+
+pub fn command() -> Command {
+    Command::new("redo")
+        .about("Apply again the changes of the last undone command of the journal")
+        .args(general_args())
+        .arg(global_arg())
+        .arg(dry_run_format_arg())
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let global = args.get_flag("global");
+    let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
+
+    ops::wints_redo::redo(RedoOptions {
+        local_basedir,
+        global_basedir,
+        global,
+        dry_run,
+        dry_run_format,
+    })
+}
@@ -1,42 +1,163 @@
+use std::path::PathBuf;
+
 use anyhow::Result;
+use clap::builder::PossibleValuesParser;
+use clap::ArgAction::{Append, SetTrue};
 use clap::{Arg, ArgMatches, Command};
 
 use wints::ops;
 use wints::ops::wints_scan::ScanOptions;
+use wints::util::noise_filter::{NoiseFilter, ALL_NOISE_FILTERS};
 
 use crate::commands::{
-    general_args, get_global_basedir, get_pathbuf_arg, get_string_arg, global_arg, module_arg,
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    get_scan_selection_options, get_string_arg, global_arg, module_arg, scan_selection_args,
 };
 
 pub fn command() -> Command {
     Command::new("scan")
         .about("Scan a directory tree for new URLs")
         .args(general_args())
+        .arg(dry_run_format_arg())
         .arg(module_arg())
         .arg(global_arg())
         .arg(
             Arg::new("path")
-                .help("Path to scan (file or directories)")
+                .help("Paths to scan (files or directories)")
                 .value_name("PATH")
                 .default_value(".")
+                .num_args(1..)
                 .index(1),
         )
+        .args(scan_selection_args())
+        .arg(
+            Arg::new("git-log")
+                .help("Also scan the commit messages of the git repository")
+                .long("git-log")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("since")
+                .help("Only scan the commits since this date")
+                .long("since")
+                .value_name("YYYY-MM-DD")
+                .requires("git-log"),
+        )
+        .arg(
+            Arg::new("git-diffs")
+                .help("Also scan the lines added or removed by each commit")
+                .long("git-diffs")
+                .requires("git-log")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("manifests")
+                .help("Add the project, docs and dependency URLs of Cargo.toml, package.json, pyproject.toml and go.mod files")
+                .long("manifests")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("infer-context")
+                .help("Use the nearest heading and the link text as context of new URLs")
+                .long("infer-context")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("report")
+                .help("Report where each scanned URL has been found")
+                .long("report")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("cache")
+                .help("Only parse the files changed since the last scan, using .wints/cache")
+                .long("cache")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("no-cache")
+                .help("Parse all the files, even if the cache is enabled in the options")
+                .long("no-cache")
+                .conflicts_with("cache")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("stale")
+                .help("Report the URLs found by a previous scan but no longer present")
+                .long("stale")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("no-noise-filter")
+                .help("Keep placeholder, private network, namespace, license and registry URLs")
+                .long("no-noise-filter")
+                .action(SetTrue),
+        )
+        .arg(
+            Arg::new("disable-noise-filter")
+                .help("Keep the URLs matching this noise filter")
+                .long("disable-noise-filter")
+                .value_name("FILTER")
+                .value_parser(PossibleValuesParser::new(
+                    ALL_NOISE_FILTERS.map(|filter| filter.name()),
+                ))
+                .action(Append),
+        )
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let module_name = get_string_arg(args, "module");
-    let scan_path = get_pathbuf_arg(args, "path");
+    let scan_paths = args
+        .get_many::<String>("path")
+        .unwrap_or_default()
+        .map(PathBuf::from)
+        .collect();
+    let selection = get_scan_selection_options(args);
     let global_module = args.get_flag("global");
+    let git_log = args.get_flag("git-log");
+    let git_since = args.get_one::<String>("since").cloned();
+    let git_diffs = args.get_flag("git-diffs");
+    let manifests = args.get_flag("manifests");
+    let infer_context = args.get_flag("infer-context");
+    let report = args.get_flag("report");
+    let stale = args.get_flag("stale");
+    let cache = match (args.get_flag("cache"), args.get_flag("no-cache")) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
+    };
+    let noise_filters = match args.get_flag("no-noise-filter") {
+        true => Some(vec![]),
+        false => None,
+    };
+    let disabled_noise_filters = args
+        .get_many::<String>("disable-noise-filter")
+        .unwrap_or_default()
+        .filter_map(|name| name.parse::<NoiseFilter>().ok())
+        .collect();
     let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
 
     ops::wints_scan::scan(ScanOptions {
         local_basedir,
         global_basedir,
         module_name,
         global_module,
-        scan_path,
+        scan_paths,
+        selection,
+        git_log,
+        git_since,
+        git_diffs,
+        manifests,
+        infer_context,
+        report,
+        cache,
+        stale,
+        noise_filters,
+        disabled_noise_filters,
         dry_run,
+        dry_run_format,
     })
 }
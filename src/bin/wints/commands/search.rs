@@ -4,7 +4,7 @@ use clap::{Arg, ArgMatches};
 use wints::ops;
 use wints::ops::wints_search::SearchOptions;
 
-use crate::commands::{get_global_basedir, get_pathbuf_arg, get_string_arg, module_arg};
+use crate::commands::{get_global_basedir, get_local_basedir, get_string_arg, module_arg};
 
 pub fn args() -> Vec<Arg> {
     vec![
@@ -18,7 +18,7 @@ pub fn args() -> Vec<Arg> {
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let module_name = get_string_arg(args, "module");
     let dry_run = args.get_flag("dry-run");
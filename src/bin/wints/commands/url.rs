@@ -1,12 +1,18 @@
 use anyhow::Result;
+use clap::ArgAction::SetTrue;
 use clap::{Arg, ArgMatches, Command};
 
 use wints::ops;
 use wints::ops::wints_url_ignore::IgnoreUrlOptions;
 use wints::ops::wints_url_ignore_glob::IgnoreGlobOptions;
+use wints::ops::wints_url_ignored::IgnoredOptions;
+use wints::ops::wints_url_unignore::UnignoreUrlOptions;
+use wints::ops::wints_url_unignore_glob::UnignoreGlobOptions;
+use wints::ops::wints_url_why::WhyOptions;
 
 use crate::commands::{
-    general_args, get_global_basedir, get_pathbuf_arg, get_string_arg, global_arg,
+    dry_run_format_arg, general_args, get_dry_run_format, get_global_basedir, get_local_basedir,
+    get_string_arg, global_arg, module_arg,
 };
 
 pub fn command() -> Command {
@@ -16,19 +22,53 @@ pub fn command() -> Command {
             Command::new("ignore")
                 .about("Add an URL to the ignore list during scan")
                 .args(general_args())
+                .arg(dry_run_format_arg())
                 .arg(global_arg())
                 .arg(
                     Arg::new("url")
-                        .help("URL to ignore")
+                        .help("URL (or pattern) to ignore")
                         .value_name("URL")
                         .required(true)
                         .index(1),
+                )
+                .arg(
+                    Arg::new("pattern")
+                        .help("Ignore a host wildcard (*.example.com) or an URL glob (http://localhost:*)")
+                        .long("pattern")
+                        .conflicts_with_all(["prefix", "regex"])
+                        .action(SetTrue),
+                )
+                .arg(
+                    Arg::new("prefix")
+                        .help("Ignore all the URLs starting with this prefix")
+                        .long("prefix")
+                        .conflicts_with("regex")
+                        .action(SetTrue),
+                )
+                .arg(
+                    Arg::new("regex")
+                        .help("Ignore all the URLs matching this regular expression")
+                        .long("regex")
+                        .action(SetTrue),
+                )
+                .arg(
+                    Arg::new("reason")
+                        .help("Why the URL is ignored")
+                        .value_name("REASON")
+                        .long("reason"),
+                )
+                .arg(
+                    Arg::new("expires")
+                        .help("Last day when the URL is ignored")
+                        .value_name("YYYY-MM-DD")
+                        .long("expires"),
                 ),
         )
         .subcommand(
             Command::new("ignore-glob")
                 .about("Add an glob/file to the ignore list during scan")
                 .args(general_args())
+                .arg(dry_run_format_arg())
                 .arg(global_arg())
                 .arg(
                     Arg::new("glob")
@@ -38,38 +78,102 @@ pub fn command() -> Command {
                         .index(1),
                 ),
         )
+        .subcommand(
+            Command::new("ignored")
+                .about("List the local, global and module ignored URLs and globs")
+                .args(general_args())
+                .arg(module_arg()),
+        )
+        .subcommand(
+            Command::new("unignore")
+                .about("Remove an URL (or pattern) from the ignore list")
+                .args(general_args())
+                .arg(dry_run_format_arg())
+                .arg(global_arg())
+                .arg(
+                    Arg::new("url")
+                        .help("URL (or pattern) to remove")
+                        .value_name("URL")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("unignore-glob")
+                .about("Remove a glob/file from the ignore list")
+                .args(general_args())
+                .arg(dry_run_format_arg())
+                .arg(global_arg())
+                .arg(
+                    Arg::new("glob")
+                        .help("Glob/file to remove")
+                        .value_name("GLOB")
+                        .required(true)
+                        .index(1),
+                ),
+        )
+        .subcommand(
+            Command::new("why")
+                .about("Explain which rule excludes an URL or a path from scans")
+                .args(general_args())
+                .arg(module_arg())
+                .arg(
+                    Arg::new("target")
+                        .help("URL or path to explain")
+                        .value_name("URL_OR_PATH")
+                        .required(true)
+                        .index(1),
+                ),
+        )
 }
 
 pub fn exec(args: &ArgMatches) -> Result<()> {
     match args.subcommand() {
         Some(("ignore", ignore_url_matches)) => exec_ignore(ignore_url_matches),
         Some(("ignore-glob", ignore_glob_matches)) => exec_ignore_glob(ignore_glob_matches),
+        Some(("ignored", ignored_matches)) => exec_ignored(ignored_matches),
+        Some(("unignore", unignore_url_matches)) => exec_unignore(unignore_url_matches),
+        Some(("unignore-glob", unignore_glob_matches)) => exec_unignore_glob(unignore_glob_matches),
+        Some(("why", why_matches)) => exec_why(why_matches),
         _ => unreachable!(),
     }
 }
 
 pub fn exec_ignore(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let url = get_string_arg(args, "url");
     let global = args.get_flag("global");
+    let pattern = args.get_flag("pattern");
+    let prefix = args.get_flag("prefix");
+    let regex = args.get_flag("regex");
+    let reason = args.get_one::<String>("reason").cloned();
+    let expires = args.get_one::<String>("expires").cloned();
     let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
 
     ops::wints_url_ignore::ignore_url(IgnoreUrlOptions {
         local_basedir,
         global_basedir,
         global,
         url,
+        pattern,
+        prefix,
+        regex,
+        reason,
+        expires,
         dry_run,
+        dry_run_format,
     })
 }
 
 pub fn exec_ignore_glob(args: &ArgMatches) -> Result<()> {
-    let local_basedir = get_pathbuf_arg(args, "config");
+    let local_basedir = get_local_basedir(args);
     let global_basedir = get_global_basedir(args);
     let glob = get_string_arg(args, "glob");
     let global = args.get_flag("global");
     let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
 
     ops::wints_url_ignore_glob::ignore_glob(IgnoreGlobOptions {
         local_basedir,
@@ -77,5 +181,69 @@ pub fn exec_ignore_glob(args: &ArgMatches) -> Result<()> {
         global,
         glob,
         dry_run,
+        dry_run_format,
+    })
+}
+
+pub fn exec_ignored(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+
+    let module_name = get_string_arg(args, "module");
+
+    ops::wints_url_ignored::ignored(IgnoredOptions {
+        local_basedir,
+        global_basedir,
+        module_name,
+    })
+}
+
+pub fn exec_unignore(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let url = get_string_arg(args, "url");
+    let global = args.get_flag("global");
+    let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
+
+    ops::wints_url_unignore::unignore_url(UnignoreUrlOptions {
+        local_basedir,
+        global_basedir,
+        global,
+        url,
+        dry_run,
+        dry_run_format,
+    })
+}
+
+pub fn exec_unignore_glob(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let glob = get_string_arg(args, "glob");
+    let global = args.get_flag("global");
+    let dry_run = args.get_flag("dry-run");
+    let dry_run_format = get_dry_run_format(args);
+
+    ops::wints_url_unignore_glob::unignore_glob(UnignoreGlobOptions {
+        local_basedir,
+        global_basedir,
+        global,
+        glob,
+        dry_run,
+        dry_run_format,
+    })
+}
+
+pub fn exec_why(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let module_name = get_string_arg(args, "module");
+    let target = get_string_arg(args, "target");
+
+    ops::wints_url_why::why(WhyOptions {
+        local_basedir,
+        global_basedir,
+        module_name,
+        target,
     })
 }
@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::{Arg, ArgMatches, Command};
+
+use wints::ops;
+use wints::ops::wints_which::WhichOptions;
+
+use crate::commands::{
+    general_args, get_global_basedir, get_local_basedir, get_string_arg, module_arg,
+};
+
+pub fn command() -> Command {
+    Command::new("which")
+        .about("Show the contexts and the scan sources of an URL")
+        .args(general_args())
+        .arg(module_arg())
+        .arg(
+            Arg::new("url")
+                .help("URL to look for")
+                .value_name("URL")
+                .required(true)
+                .index(1),
+        )
+}
+
+pub fn exec(args: &ArgMatches) -> Result<()> {
+    let local_basedir = get_local_basedir(args);
+    let global_basedir = get_global_basedir(args);
+    let module_name = get_string_arg(args, "module");
+    let url = get_string_arg(args, "url");
+
+    ops::wints_which::which(WhichOptions {
+        local_basedir,
+        global_basedir,
+        module_name,
+        url,
+    })
+}
@@ -1,9 +1,60 @@
+use std::collections::BTreeMap;
+
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::core::forge::ForgeConfig;
+use crate::core::ignore_pattern::IgnorePattern;
+use crate::util::html_links::parse_site_root;
+use crate::util::noise_filter::{NoiseFilter, ALL_NOISE_FILTERS};
+use crate::util::scan_selection::{parse_filesize, ScanSelection};
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WintsConfig {
     pub version: u8,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub ignored_urls: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ignored_patterns: Option<Vec<IgnorePattern>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tracking_params: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scan: Option<ScanConfig>,
+    // Layout of the self-hosted forges, by git host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forges: Option<BTreeMap<String, ForgeConfig>>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<bool>,
+    // Enabled noise filters, all of them when not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub noise_filters: Option<Vec<NoiseFilter>>,
+    // File selection, see ScanSelection
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub types_not: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_depth: Option<usize>,
+    // Like '100K' or '1M'
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_filesize: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hidden: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub follow_symlinks: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gitignore: Option<bool>,
+    // Extensions of the source files where only comments and docstrings are scanned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments_only: Option<Vec<String>>,
+    // URL of the scanned folder, to resolve the relative links of HTML files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_root: Option<String>,
 }
 
 impl WintsConfig {
@@ -11,13 +62,66 @@ impl WintsConfig {
         WintsConfig {
             version: 1,
             ignored_urls: None,
+            ignored_patterns: None,
+            tracking_params: None,
+            scan: None,
+            forges: None,
+        }
+    }
+}
+
+impl ScanConfig {
+    pub fn selection(&self) -> Result<ScanSelection> {
+        let default = ScanSelection::default();
+        let max_filesize = match &self.max_filesize {
+            None => None,
+            Some(max_filesize) => Some(parse_filesize(max_filesize)?),
+        };
+        Ok(ScanSelection {
+            types: self.types.clone().unwrap_or_default(),
+            types_not: self.types_not.clone().unwrap_or_default(),
+            max_depth: self.max_depth,
+            max_filesize,
+            hidden: self.hidden.unwrap_or(default.hidden),
+            follow_symlinks: self.follow_symlinks.unwrap_or(default.follow_symlinks),
+            gitignore: self.gitignore.unwrap_or(default.gitignore),
+        })
+    }
+
+    pub fn site_root(&self) -> Result<Option<Url>> {
+        self.site_root.as_deref().map(parse_site_root).transpose()
+    }
+
+    pub fn enabled_noise_filters(&self) -> Vec<NoiseFilter> {
+        self.noise_filters
+            .clone()
+            .unwrap_or_else(|| ALL_NOISE_FILTERS.to_vec())
+    }
+
+    // Values from the first configuration take precedence over the second one
+    pub fn merge(first: Option<ScanConfig>, second: Option<ScanConfig>) -> ScanConfig {
+        let first = first.unwrap_or_default();
+        let second = second.unwrap_or_default();
+        ScanConfig {
+            cache: first.cache.or(second.cache),
+            noise_filters: first.noise_filters.or(second.noise_filters),
+            types: first.types.or(second.types),
+            types_not: first.types_not.or(second.types_not),
+            max_depth: first.max_depth.or(second.max_depth),
+            max_filesize: first.max_filesize.or(second.max_filesize),
+            hidden: first.hidden.or(second.hidden),
+            follow_symlinks: first.follow_symlinks.or(second.follow_symlinks),
+            gitignore: first.gitignore.or(second.gitignore),
+            comments_only: first.comments_only.or(second.comments_only),
+            site_root: first.site_root.or(second.site_root),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::core::config::WintsConfig;
+    use crate::core::config::{ScanConfig, WintsConfig};
+    use crate::util::noise_filter::NoiseFilter;
 
     fn print_serde_error_if_any(result: &serde_yaml::Result<WintsConfig>) {
         if let Err(err) = &result {
@@ -72,6 +176,124 @@ mod tests {
         assert_eq!(vec!["https://site.tld"], config.ignored_urls.unwrap());
     }
 
+    #[test]
+    fn deserialize_with_scan_options() {
+        let yaml = r#"
+            version: 1
+            scan:
+              cache: true
+        "#;
+
+        let result: serde_yaml::Result<WintsConfig> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&result);
+        assert!(result.is_ok());
+        let config = result.unwrap();
+        assert!(config.ignored_urls.is_none());
+        assert_eq!(Some(true), config.scan.unwrap().cache);
+    }
+
+    #[test]
+    fn deserialize_with_file_selection() {
+        let yaml = r#"
+            version: 1
+            scan:
+              types:
+              - md
+              max_depth: 3
+              max_filesize: 1M
+              hidden: true
+              gitignore: false
+        "#;
+
+        let result: serde_yaml::Result<WintsConfig> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&result);
+        let selection = result.unwrap().scan.unwrap().selection().unwrap();
+        assert_eq!(vec!["md".to_string()], selection.types);
+        assert_eq!(Some(3), selection.max_depth);
+        assert_eq!(Some(1024 * 1024), selection.max_filesize);
+        assert!(selection.hidden);
+        assert!(!selection.follow_symlinks);
+        assert!(!selection.gitignore);
+    }
+
+    #[test]
+    fn deserialize_with_noise_filters() {
+        let yaml = r#"
+            version: 1
+            scan:
+              noise_filters:
+              - placeholder
+              - private-network
+        "#;
+
+        let result: serde_yaml::Result<WintsConfig> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&result);
+        assert!(result.is_ok());
+        assert_eq!(
+            Some(vec![NoiseFilter::Placeholder, NoiseFilter::PrivateNetwork]),
+            result.unwrap().scan.unwrap().noise_filters
+        );
+    }
+
+    #[test]
+    fn deserialize_with_ignored_patterns() {
+        let yaml = r#"
+            version: 1
+            ignored_patterns:
+            - host: "*.example.com"
+              reason: placeholders
+            - regex: ^http://localhost
+        "#;
+
+        let result: serde_yaml::Result<WintsConfig> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&result);
+        assert!(result.is_ok());
+        let config = result.unwrap();
+        assert!(config.ignored_urls.is_none());
+        assert_eq!(2, config.ignored_patterns.unwrap().len());
+    }
+
+    #[test]
+    fn deserialize_with_tracking_params() {
+        let yaml = r#"
+            version: 1
+            tracking_params:
+            - ref
+            - utm_*
+        "#;
+
+        let result: serde_yaml::Result<WintsConfig> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&result);
+        assert!(result.is_ok());
+        assert_eq!(
+            Some(vec!["ref".to_string(), "utm_*".to_string()]),
+            result.unwrap().tracking_params
+        );
+    }
+
+    #[test]
+    fn merge_scan_options() {
+        let local = ScanConfig {
+            cache: Some(false),
+            ..ScanConfig::default()
+        };
+        let global = ScanConfig {
+            cache: Some(true),
+            noise_filters: Some(vec![NoiseFilter::License]),
+            ..ScanConfig::default()
+        };
+        assert_eq!(
+            Some(false),
+            ScanConfig::merge(Some(local.clone()), Some(global.clone())).cache
+        );
+        assert_eq!(
+            Some(vec![NoiseFilter::License]),
+            ScanConfig::merge(Some(local), Some(global.clone())).noise_filters
+        );
+        assert_eq!(Some(true), ScanConfig::merge(None, Some(global)).cache);
+        assert_eq!(None, ScanConfig::merge(None, None).cache);
+    }
+
     #[test]
     fn no_ignored_urls() {
         let config = WintsConfig::empty_config();
@@ -0,0 +1,236 @@
+use serde::{Deserialize, Serialize};
+
+use crate::util::git_remote::RemoteLocation;
+
+// Replaced by the web URL of the repository, like 'https://github.com/rlespinasse/wints'
+const REPOSITORY_PLACEHOLDER: &str = "{repository}";
+// Replaced by the path of the repository on the forge, like 'rlespinasse/wints'
+const PATH_PLACEHOLDER: &str = "{path}";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+    Bitbucket,
+    Gitea,
+}
+
+// Layout of a self-hosted forge in options.yaml, the pages not set are the ones of its kind,
+// an empty page is not generated
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ForgeConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<ForgeKind>,
+    // When the web pages are not served by the git host, like 'https://code.corp.tld'
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub web_url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issues: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_requests: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub releases: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wiki: Option<String>,
+}
+
+// Pages of a repository as [code, issues, pull requests, ci, releases, wiki]
+type ForgeLayout = [&'static str; 6];
+
+const CONTEXTS: [&str; 6] = [
+    "repository code",
+    "repository issues",
+    "repository pull requests",
+    "repository ci",
+    "repository releases",
+    "repository wiki",
+];
+
+impl ForgeKind {
+    // Public instances, and self-hosted ones named after their software
+    pub fn detect(host: &str) -> Option<ForgeKind> {
+        match host {
+            "github.com" => Some(ForgeKind::Github),
+            "gitlab.com" => Some(ForgeKind::Gitlab),
+            "bitbucket.org" => Some(ForgeKind::Bitbucket),
+            "gitea.com" | "codeberg.org" => Some(ForgeKind::Gitea),
+            _ if host.contains("gitlab") => Some(ForgeKind::Gitlab),
+            _ if host.contains("gitea") || host.contains("forgejo") => Some(ForgeKind::Gitea),
+            _ if host.contains("github") => Some(ForgeKind::Github),
+            _ => None,
+        }
+    }
+
+    fn layout(&self) -> ForgeLayout {
+        match self {
+            ForgeKind::Github => [
+                "{repository}",
+                "{repository}/issues",
+                "{repository}/pulls",
+                "{repository}/actions",
+                "{repository}/releases",
+                "{repository}/wiki",
+            ],
+            ForgeKind::Gitlab => [
+                "{repository}",
+                "{repository}/-/issues",
+                "{repository}/-/merge_requests",
+                "{repository}/-/pipelines",
+                "{repository}/-/releases",
+                "{repository}/-/wikis",
+            ],
+            ForgeKind::Bitbucket => [
+                "{repository}",
+                "{repository}/issues",
+                "{repository}/pull-requests",
+                "{repository}/pipelines",
+                "{repository}/downloads",
+                "{repository}/wiki",
+            ],
+            ForgeKind::Gitea => [
+                "{repository}",
+                "{repository}/issues",
+                "{repository}/pulls",
+                "{repository}/actions",
+                "{repository}/releases",
+                "{repository}/wiki",
+            ],
+        }
+    }
+}
+
+impl ForgeConfig {
+    fn pages(&self) -> [&Option<String>; 6] {
+        [
+            &self.code,
+            &self.issues,
+            &self.pull_requests,
+            &self.ci,
+            &self.releases,
+            &self.wiki,
+        ]
+    }
+}
+
+// Contexts and URLs of a repository, only its code when the forge is unknown
+pub fn repository_pages(
+    location: &RemoteLocation,
+    config: Option<&ForgeConfig>,
+) -> Vec<(String, String)> {
+    let default_config = ForgeConfig::default();
+    let config = config.unwrap_or(&default_config);
+    let web_url = config
+        .web_url
+        .clone()
+        .unwrap_or_else(|| format!("https://{}", location.host));
+    let repository_url = format!("{}/{}", web_url.trim_end_matches('/'), location.path);
+    let layout = config
+        .kind
+        .or_else(|| ForgeKind::detect(&location.host))
+        .map(|kind| kind.layout())
+        .unwrap_or([REPOSITORY_PLACEHOLDER, "", "", "", "", ""]);
+
+    CONTEXTS
+        .iter()
+        .zip(layout)
+        .zip(config.pages())
+        .map(|((context, default_page), page)| (context, page.as_deref().unwrap_or(default_page)))
+        .filter(|(_, page)| !page.is_empty())
+        .map(|(context, page)| {
+            (
+                context.to_string(),
+                page.replace(REPOSITORY_PLACEHOLDER, &repository_url)
+                    .replace(PATH_PLACEHOLDER, &location.path),
+            )
+        })
+        .collect()
+}
+
+pub fn is_known_forge(location: &RemoteLocation, config: Option<&ForgeConfig>) -> bool {
+    config.is_some() || ForgeKind::detect(&location.host).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::forge::*;
+
+    fn location(host: &str, path: &str) -> RemoteLocation {
+        RemoteLocation {
+            host: host.to_string(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn github_pages() {
+        let pages = repository_pages(&location("github.com", "rlespinasse/wints"), None);
+        assert_eq!(6, pages.len());
+        assert_eq!(
+            (
+                "repository code".to_string(),
+                "https://github.com/rlespinasse/wints".to_string()
+            ),
+            pages[0]
+        );
+        assert_eq!(
+            (
+                "repository pull requests".to_string(),
+                "https://github.com/rlespinasse/wints/pulls".to_string()
+            ),
+            pages[2]
+        );
+    }
+
+    #[test]
+    fn gitlab_pages() {
+        let pages = repository_pages(&location("gitlab.corp.tld", "group/project"), None);
+        assert_eq!(
+            (
+                "repository ci".to_string(),
+                "https://gitlab.corp.tld/group/project/-/pipelines".to_string()
+            ),
+            pages[3]
+        );
+    }
+
+    #[test]
+    fn unknown_forge_pages() {
+        let location = location("git.corp.tld", "team/repo");
+        assert!(!is_known_forge(&location, None));
+        assert_eq!(
+            vec![(
+                "repository code".to_string(),
+                "https://git.corp.tld/team/repo".to_string()
+            )],
+            repository_pages(&location, None)
+        );
+    }
+
+    #[test]
+    fn configured_forge_pages() {
+        let yaml = r#"
+            kind: gitea
+            web_url: https://code.corp.tld/
+            ci: https://ci.corp.tld/{path}
+            wiki: ""
+        "#;
+        let config: ForgeConfig = serde_yaml::from_str(yaml).unwrap();
+        let pages = repository_pages(&location("git.corp.tld", "team/repo"), Some(&config));
+        let urls: Vec<String> = pages.into_iter().map(|(_, url)| url).collect();
+        assert_eq!(
+            vec![
+                "https://code.corp.tld/team/repo",
+                "https://code.corp.tld/team/repo/issues",
+                "https://code.corp.tld/team/repo/pulls",
+                "https://ci.corp.tld/team/repo",
+                "https://code.corp.tld/team/repo/releases",
+            ],
+            urls
+        );
+    }
+}
@@ -0,0 +1,338 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use anyhow::{anyhow, Result};
+use glob::Pattern;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::util::date;
+use crate::util::url_normaliser::UrlNormaliser;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IgnorePattern {
+    #[serde(flatten)]
+    pub rule: IgnoreRule,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    // Last day (YYYY-MM-DD) when the pattern is applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IgnoreRule {
+    // Exact URL, compared in its canonical form
+    Url(String),
+    // Host wildcard like '*.example.com' or 'localhost:*'
+    Host(String),
+    Prefix(String),
+    Glob(String),
+    Regex(String),
+}
+
+impl IgnorePattern {
+    pub fn new(
+        rule: IgnoreRule,
+        reason: Option<String>,
+        expires: Option<String>,
+    ) -> Result<IgnorePattern> {
+        let pattern = IgnorePattern {
+            rule,
+            reason,
+            expires,
+        };
+        pattern.validate()?;
+        Ok(pattern)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match &self.rule {
+            IgnoreRule::Host(host) | IgnoreRule::Glob(host) => {
+                Pattern::new(host).map_err(|err| anyhow!("invalid glob '{}': {}", host, err))?;
+            }
+            IgnoreRule::Regex(regex) => {
+                Regex::new(regex).map_err(|err| anyhow!("invalid regex '{}': {}", regex, err))?;
+            }
+            IgnoreRule::Url(_) | IgnoreRule::Prefix(_) => {}
+        }
+        match &self.expires {
+            Some(expires) if !date::is_valid_date(expires) => Err(anyhow!(
+                "invalid expiry date '{}', expected YYYY-MM-DD",
+                expires
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // Check a single pattern, expired or not, to explain why an URL is ignored
+    pub fn matches(&self, normaliser: &UrlNormaliser, url: &str) -> bool {
+        match compile(normaliser, &self.rule) {
+            None => false,
+            Some(rule) => matches(&rule, url, &normaliser.canonicalise(url)),
+        }
+    }
+
+    pub fn is_expired(&self, today: &str) -> bool {
+        match &self.expires {
+            None => false,
+            Some(expires) => expires.as_str() < today,
+        }
+    }
+}
+
+impl IgnoreRule {
+    pub fn value(&self) -> &str {
+        match self {
+            IgnoreRule::Url(value)
+            | IgnoreRule::Host(value)
+            | IgnoreRule::Prefix(value)
+            | IgnoreRule::Glob(value)
+            | IgnoreRule::Regex(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for IgnoreRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IgnoreRule::Url(value) => write!(f, "url '{}'", value),
+            IgnoreRule::Host(value) => write!(f, "host '{}'", value),
+            IgnoreRule::Prefix(value) => write!(f, "prefix '{}'", value),
+            IgnoreRule::Glob(value) => write!(f, "glob '{}'", value),
+            IgnoreRule::Regex(value) => write!(f, "regex '{}'", value),
+        }
+    }
+}
+
+enum CompiledRule {
+    Url(String),
+    Host(Pattern),
+    Prefix(String),
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+// Exact URLs and patterns compiled once, to check a lot of scanned URLs
+pub struct UrlIgnoreList {
+    normaliser: UrlNormaliser,
+    urls: HashSet<String>,
+    rules: Vec<CompiledRule>,
+}
+
+impl UrlIgnoreList {
+    pub fn new(
+        normaliser: UrlNormaliser,
+        urls: Vec<String>,
+        patterns: Vec<IgnorePattern>,
+    ) -> UrlIgnoreList {
+        let today = date::today();
+        let urls = urls
+            .iter()
+            .map(|url| normaliser.canonicalise(url))
+            .collect();
+        let rules = patterns
+            .iter()
+            .filter(|pattern| !pattern.is_expired(&today))
+            .filter_map(|pattern| compile(&normaliser, &pattern.rule))
+            .collect();
+        UrlIgnoreList {
+            normaliser,
+            urls,
+            rules,
+        }
+    }
+
+    pub fn is_ignored(&self, url: &str) -> bool {
+        let canonical_url = self.normaliser.canonicalise(url);
+        self.urls.contains(&canonical_url)
+            || self
+                .rules
+                .iter()
+                .any(|rule| matches(rule, url, &canonical_url))
+    }
+}
+
+fn compile(normaliser: &UrlNormaliser, rule: &IgnoreRule) -> Option<CompiledRule> {
+    match rule {
+        IgnoreRule::Url(url) => Some(CompiledRule::Url(normaliser.canonicalise(url))),
+        IgnoreRule::Host(host) => Pattern::new(&host.to_lowercase())
+            .ok()
+            .map(CompiledRule::Host),
+        IgnoreRule::Prefix(prefix) => Some(CompiledRule::Prefix(prefix.clone())),
+        IgnoreRule::Glob(glob) => Pattern::new(glob).ok().map(CompiledRule::Glob),
+        IgnoreRule::Regex(regex) => Regex::new(regex).ok().map(CompiledRule::Regex),
+    }
+}
+
+fn matches(rule: &CompiledRule, url: &str, canonical_url: &str) -> bool {
+    match rule {
+        CompiledRule::Url(ignored_url) => ignored_url == canonical_url,
+        CompiledRule::Host(pattern) => match Url::parse(canonical_url) {
+            Err(_) => false,
+            Ok(parsed_url) => {
+                let host = parsed_url.host_str().unwrap_or_default();
+                match (
+                    pattern.as_str().contains(':'),
+                    parsed_url.port_or_known_default(),
+                ) {
+                    (true, Some(port)) => pattern.matches(&format!("{}:{}", host, port)),
+                    _ => pattern.matches(host),
+                }
+            }
+        },
+        CompiledRule::Prefix(prefix) => {
+            url.starts_with(prefix) || canonical_url.starts_with(prefix)
+        }
+        CompiledRule::Glob(pattern) => pattern.matches(url) || pattern.matches(canonical_url),
+        CompiledRule::Regex(regex) => regex.is_match(url) || regex.is_match(canonical_url),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::ignore_pattern::*;
+
+    fn pattern(rule: IgnoreRule) -> IgnorePattern {
+        IgnorePattern::new(rule, None, None).unwrap()
+    }
+
+    fn ignore_list(patterns: Vec<IgnorePattern>) -> UrlIgnoreList {
+        UrlIgnoreList::new(UrlNormaliser::default(), vec![], patterns)
+    }
+
+    #[test]
+    fn deserialize() {
+        let yaml = r#"
+            - url: https://site.tld
+            - host: "*.example.com"
+              reason: placeholders
+            - prefix: https://github.com/org/repo/blob/
+            - glob: http://localhost:*
+            - regex: ^https?://internal\.
+              expires: 2030-12-31
+        "#;
+
+        let patterns: Vec<IgnorePattern> = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(5, patterns.len());
+        assert_eq!(
+            IgnoreRule::Host("*.example.com".to_string()),
+            patterns[1].rule
+        );
+        assert_eq!(Some("placeholders".to_string()), patterns[1].reason);
+        assert_eq!(Some("2030-12-31".to_string()), patterns[4].expires);
+    }
+
+    #[test]
+    fn serialize() {
+        let pattern = IgnorePattern::new(
+            IgnoreRule::Glob("http://localhost:*".to_string()),
+            Some("local servers".to_string()),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            "glob: http://localhost:*\nreason: local servers\n",
+            serde_yaml::to_string(&pattern).unwrap()
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert!(IgnorePattern::new(IgnoreRule::Regex("(".to_string()), None, None).is_err());
+        assert!(IgnorePattern::new(IgnoreRule::Glob("[".to_string()), None, None).is_err());
+        assert!(IgnorePattern::new(
+            IgnoreRule::Url("https://site.tld".to_string()),
+            None,
+            Some("next year".to_string())
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn ignore_exact_urls() {
+        let list = UrlIgnoreList::new(
+            UrlNormaliser::default(),
+            vec!["https://site.tld/".to_string()],
+            vec![pattern(IgnoreRule::Url("https://other.tld".to_string()))],
+        );
+        assert!(list.is_ignored("HTTPS://site.tld"));
+        assert!(list.is_ignored("https://other.tld/"));
+        assert!(!list.is_ignored("https://site.tld/page"));
+    }
+
+    #[test]
+    fn ignore_hosts() {
+        let list = ignore_list(vec![
+            pattern(IgnoreRule::Host("*.example.com".to_string())),
+            pattern(IgnoreRule::Host("localhost:*".to_string())),
+        ]);
+        assert!(list.is_ignored("https://www.example.com/page"));
+        assert!(list.is_ignored("http://localhost:8080/api"));
+        assert!(list.is_ignored("http://localhost/api"));
+        assert!(!list.is_ignored("https://example.com.evil.tld"));
+    }
+
+    #[test]
+    fn ignore_prefixes() {
+        let list = ignore_list(vec![pattern(IgnoreRule::Prefix(
+            "https://github.com/org/repo/blob/".to_string(),
+        ))]);
+        assert!(list.is_ignored("https://github.com/org/repo/blob/main/README.md"));
+        assert!(!list.is_ignored("https://github.com/org/repo/issues"));
+    }
+
+    #[test]
+    fn ignore_globs() {
+        let list = ignore_list(vec![pattern(IgnoreRule::Glob(
+            "http://localhost:*".to_string(),
+        ))]);
+        assert!(list.is_ignored("http://localhost:8080/api"));
+        assert!(!list.is_ignored("http://127.0.0.1:8080/api"));
+    }
+
+    #[test]
+    fn ignore_regexes() {
+        let list = ignore_list(vec![pattern(IgnoreRule::Regex(
+            r"^https?://internal\.".to_string(),
+        ))]);
+        assert!(list.is_ignored("http://internal.corp.tld/wiki"));
+        assert!(!list.is_ignored("https://external.tld"));
+    }
+
+    #[test]
+    fn single_pattern_matches() {
+        let normaliser = UrlNormaliser::default();
+        let expired = IgnorePattern::new(
+            IgnoreRule::Host("*.corp.tld".to_string()),
+            None,
+            Some("2000-01-01".to_string()),
+        )
+        .unwrap();
+        assert!(expired.matches(&normaliser, "https://wiki.corp.tld"));
+        assert!(!expired.matches(&normaliser, "https://wiki.tld"));
+        assert_eq!("*.corp.tld", expired.rule.value());
+    }
+
+    #[test]
+    fn expired_patterns_are_not_applied() {
+        let list = ignore_list(vec![
+            IgnorePattern::new(
+                IgnoreRule::Host("expired.tld".to_string()),
+                None,
+                Some("2000-01-01".to_string()),
+            )
+            .unwrap(),
+            IgnorePattern::new(
+                IgnoreRule::Host("active.tld".to_string()),
+                None,
+                Some("2999-12-31".to_string()),
+            )
+            .unwrap(),
+        ]);
+        assert!(!list.is_ignored("https://expired.tld"));
+        assert!(list.is_ignored("https://active.tld"));
+    }
+}
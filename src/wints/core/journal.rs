@@ -0,0 +1,288 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::util::date;
+use crate::util::file_changes::FileChange;
+use crate::util::file_store;
+
+// Oldest entries are dropped when a new one is recorded
+const JOURNAL_SIZE: usize = 100;
+
+// Files changed by a command, with their content before and after
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub id: u64,
+    pub date: String,
+    pub command: String,
+    // Undone entries can be redone until a new entry is recorded
+    #[serde(default)]
+    pub undone: bool,
+    pub files: Vec<FileChange>,
+}
+
+// One file per entry in the journal folder of the local configuration
+#[derive(Debug)]
+pub struct WintsJournal {
+    folder: PathBuf,
+}
+
+impl JournalEntry {
+    // Changes restoring the files as they were before the entry
+    fn undo_changes(&self) -> Vec<FileChange> {
+        self.files
+            .iter()
+            .rev()
+            .map(|file| FileChange {
+                path: file.path.clone(),
+                before: file.after.clone(),
+                after: file.before.clone(),
+            })
+            .collect()
+    }
+}
+
+impl WintsJournal {
+    pub fn new(folder: PathBuf) -> WintsJournal {
+        WintsJournal { folder }
+    }
+
+    // Entries from the oldest to the newest
+    pub fn entries(&self) -> Result<Vec<JournalEntry>> {
+        let mut entries = vec![];
+        for path in glob::glob(format!("{}/*.yaml", self.folder.display()).as_str())?.flatten() {
+            let content = fs::read_to_string(&path)?;
+            let entry: JournalEntry = serde_yaml::from_str(&content)
+                .map_err(|err| anyhow!("invalid journal entry '{}': {}", path.display(), err))?;
+            entries.push(entry);
+        }
+        entries.sort_by_key(|entry| entry.id);
+        Ok(entries)
+    }
+
+    // A new entry drops the undone ones, they can no longer be redone
+    pub fn record(&self, command: String, changes: &[FileChange]) -> Result<JournalEntry> {
+        let entries = self.entries()?;
+        let id = entries.last().map(|entry| entry.id + 1).unwrap_or(1);
+        let done: Vec<&JournalEntry> = entries.iter().filter(|entry| !entry.undone).collect();
+        let dropped = done.len().saturating_sub(JOURNAL_SIZE - 1);
+        for entry in entries
+            .iter()
+            .filter(|entry| entry.undone)
+            .chain(done.into_iter().take(dropped))
+        {
+            fs::remove_file(self.entry_path(entry.id))?;
+        }
+
+        let entry = JournalEntry {
+            id,
+            date: date::now(),
+            command,
+            undone: false,
+            files: changes.to_vec(),
+        };
+        self.write_entry(&entry)?;
+        Ok(entry)
+    }
+
+    // Newest entry which is not undone
+    pub fn last_done(&self) -> Result<Option<JournalEntry>> {
+        Ok(self
+            .entries()?
+            .into_iter()
+            .rev()
+            .find(|entry| !entry.undone))
+    }
+
+    // Oldest undone entry
+    pub fn first_undone(&self) -> Result<Option<JournalEntry>> {
+        Ok(self.entries()?.into_iter().find(|entry| entry.undone))
+    }
+
+    // Files of the entry back to their content before it, nothing is written in dry-run mode
+    pub fn undo(&self, entry: &JournalEntry, dry_run: bool) -> Result<Vec<FileChange>> {
+        self.apply(entry, entry.undo_changes(), true, dry_run)
+    }
+
+    // Files of an undone entry back to their content after it
+    pub fn redo(&self, entry: &JournalEntry, dry_run: bool) -> Result<Vec<FileChange>> {
+        self.apply(entry, entry.files.clone(), false, dry_run)
+    }
+
+    // Paths of the files moved from a folder to another one
+    pub fn relocate(&self, from: &Path, to: &Path) -> Result<()> {
+        for mut entry in self.entries()? {
+            let mut relocated = false;
+            for file in entry.files.iter_mut() {
+                if let Ok(relative_path) = file.path.strip_prefix(from) {
+                    file.path = to.join(relative_path);
+                    relocated = true;
+                }
+            }
+            if relocated {
+                self.write_entry(&entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply(
+        &self,
+        entry: &JournalEntry,
+        changes: Vec<FileChange>,
+        undone: bool,
+        dry_run: bool,
+    ) -> Result<Vec<FileChange>> {
+        // Nothing is written when a file has been changed since the entry
+        for change in &changes {
+            if fs::read_to_string(&change.path).ok() != change.before {
+                return Err(anyhow!(
+                    "'{}' has been changed since the journal entry #{}, it cannot be {}",
+                    change.path.display(),
+                    entry.id,
+                    if undone { "undone" } else { "redone" }
+                ));
+            }
+        }
+        if dry_run {
+            return Ok(changes);
+        }
+        for change in &changes {
+            match &change.after {
+                Some(after) => file_store::write_atomically(&change.path, after.as_bytes())?,
+                None => fs::remove_file(&change.path)?,
+            }
+        }
+        let mut entry = entry.clone();
+        entry.undone = undone;
+        self.write_entry(&entry)?;
+        Ok(changes)
+    }
+
+    fn write_entry(&self, entry: &JournalEntry) -> Result<()> {
+        file_store::write_atomically(
+            &self.entry_path(entry.id),
+            serde_yaml::to_string(entry)?.as_bytes(),
+        )
+    }
+
+    fn entry_path(&self, id: u64) -> PathBuf {
+        self.folder.join(format!("{:06}.yaml", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::core::journal::*;
+
+    #[test]
+    fn undo_and_redo_an_entry() -> Result<()> {
+        let dir = tempdir()?;
+        let journal = WintsJournal::new(dir.path().join("journal"));
+        let module_path = dir.path().join("modules").join("main.yaml");
+        let ignore_path = dir.path().join("ignore");
+        fs::create_dir_all(dir.path().join("modules"))?;
+        fs::write(&module_path, "version: 1\nelements: []\n")?;
+        fs::write(&ignore_path, "target\n")?;
+
+        let entry = journal.record(
+            "wints add https://docs.rs docs".to_string(),
+            &[
+                FileChange {
+                    path: module_path.clone(),
+                    before: Some("version: 1\nelements: []\n".to_string()),
+                    after: Some("version: 1\nelements:\n- context: docs\n".to_string()),
+                },
+                FileChange {
+                    path: dir.path().join("options.yaml"),
+                    before: None,
+                    after: Some("scan: {}\n".to_string()),
+                },
+            ],
+        )?;
+        fs::write(&module_path, "version: 1\nelements:\n- context: docs\n")?;
+        fs::write(dir.path().join("options.yaml"), "scan: {}\n")?;
+
+        assert_eq!(Some(entry.clone()), journal.last_done()?);
+        assert_eq!(2, journal.undo(&entry, true)?.len());
+        assert!(dir.path().join("options.yaml").exists());
+
+        journal.undo(&entry, false)?;
+        assert_eq!(
+            "version: 1\nelements: []\n",
+            fs::read_to_string(&module_path)?
+        );
+        assert!(!dir.path().join("options.yaml").exists());
+        assert_eq!(None, journal.last_done()?);
+        let undone = journal.first_undone()?.unwrap();
+        assert!(undone.undone);
+
+        journal.redo(&undone, false)?;
+        assert_eq!(
+            "version: 1\nelements:\n- context: docs\n",
+            fs::read_to_string(&module_path)?
+        );
+        assert_eq!(
+            "scan: {}\n",
+            fs::read_to_string(dir.path().join("options.yaml"))?
+        );
+        assert_eq!(None, journal.first_undone()?);
+        assert_eq!("target\n", fs::read_to_string(&ignore_path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn undo_a_file_changed_since_the_entry() -> Result<()> {
+        let dir = tempdir()?;
+        let journal = WintsJournal::new(dir.path().join("journal"));
+        let path = dir.path().join("ignore");
+        let entry = journal.record(
+            "wints url ignore-glob target".to_string(),
+            &[FileChange {
+                path: path.clone(),
+                before: None,
+                after: Some("target\n".to_string()),
+            }],
+        )?;
+        fs::write(&path, "target\ndist\n")?;
+        assert!(journal.undo(&entry, false).is_err());
+        assert_eq!("target\ndist\n", fs::read_to_string(&path)?);
+        Ok(())
+    }
+
+    #[test]
+    fn record_drops_the_undone_entries() -> Result<()> {
+        let dir = tempdir()?;
+        let journal = WintsJournal::new(dir.path().join("journal"));
+        let path = dir.path().join("ignore");
+        let change = |before: Option<&str>, after: &str| FileChange {
+            path: path.clone(),
+            before: before.map(str::to_string),
+            after: Some(after.to_string()),
+        };
+        journal.record("first".to_string(), &[change(None, "a\n")])?;
+        fs::write(&path, "a\n")?;
+        let second = journal.record("second".to_string(), &[change(Some("a\n"), "b\n")])?;
+        fs::write(&path, "b\n")?;
+        journal.undo(&second, false)?;
+
+        journal.record("third".to_string(), &[change(Some("a\n"), "c\n")])?;
+        let commands: Vec<(u64, String)> = journal
+            .entries()?
+            .into_iter()
+            .map(|entry| (entry.id, entry.command))
+            .collect();
+        assert_eq!(
+            vec![(1, "first".to_string()), (3, "third".to_string())],
+            commands
+        );
+        Ok(())
+    }
+}
@@ -1,3 +1,7 @@
 pub mod config;
+pub mod forge;
+pub mod ignore_pattern;
+pub mod journal;
 pub mod module;
 pub mod storage;
+pub mod template;
@@ -1,11 +1,24 @@
+use std::collections::BTreeMap;
+
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use serde::{Deserialize, Serialize};
 
+use crate::core::ignore_pattern::IgnorePattern;
+
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct WintsModule {
     pub version: u8,
     elements: Vec<Element>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    sources: BTreeMap<String, Vec<String>>,
+    // Ignore rules only applied when scanning for this module
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignored_urls: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignored_patterns: Vec<IgnorePattern>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignored_globs: Vec<String>,
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -19,6 +32,10 @@ impl WintsModule {
         WintsModule {
             version: 1,
             elements: vec![],
+            sources: Default::default(),
+            ignored_urls: vec![],
+            ignored_patterns: vec![],
+            ignored_globs: vec![],
         }
     }
 
@@ -43,6 +60,10 @@ impl WintsModule {
                     urls: vec!["https://github.com/rlespinasse/wints/blob/v0.x/USAGE.adoc".into()],
                 },
             ],
+            sources: Default::default(),
+            ignored_urls: vec![],
+            ignored_patterns: vec![],
+            ignored_globs: vec![],
         }
     }
 
@@ -64,6 +85,60 @@ impl WintsModule {
         module.elements = updated_elements
     }
 
+    pub fn record_sources(module: &mut WintsModule, url: String, sources: Vec<String>) {
+        let current_sources = module.sources.entry(url).or_default();
+        for source in sources {
+            if !current_sources.contains(&source) {
+                current_sources.push(source);
+            }
+        }
+    }
+
+    // URLs are removed from all contexts, contexts left without URLs are removed too
+    pub fn remove_urls(module: &mut WintsModule, urls: &[String]) {
+        for element in module.elements.iter_mut() {
+            element.urls.retain(|url| !urls.contains(url));
+        }
+        module.elements.retain(|element| !element.urls.is_empty());
+        module.sources.retain(|url, _| !urls.contains(url));
+    }
+
+    pub fn ignored_urls(&self) -> Vec<String> {
+        self.ignored_urls.clone()
+    }
+
+    pub fn ignored_patterns(&self) -> Vec<IgnorePattern> {
+        self.ignored_patterns.clone()
+    }
+
+    pub fn ignored_globs(&self) -> Vec<String> {
+        self.ignored_globs.clone()
+    }
+
+    pub fn list_of_urls_with_sources(&self) -> Vec<String> {
+        self.sources.keys().cloned().collect()
+    }
+
+    pub fn list_of_sources(&self, url: &str) -> Vec<String> {
+        self.sources.get(url).cloned().unwrap_or_default()
+    }
+
+    pub fn list_of_contexts_with_url(&self, url: &str) -> Vec<String> {
+        self.elements
+            .iter()
+            .filter(|element| element.urls.iter().any(|element_url| element_url == url))
+            .map(|element| element.context.clone())
+            .collect()
+    }
+
+    pub fn list_of_context_urls(&self, context: &str) -> Vec<String> {
+        self.elements
+            .iter()
+            .filter(|element| element.context == context)
+            .flat_map(|element| element.urls.clone())
+            .collect()
+    }
+
     pub fn list_of_all_contexts(&self) -> Vec<String> {
         self.elements
             .iter()
@@ -200,6 +275,27 @@ mod tests {
         assert_eq!(1, module.unwrap().version)
     }
 
+    #[test]
+    fn deserialize_with_ignore_rules() {
+        let yaml = r#"
+            version: 1
+            elements: []
+            ignored_urls:
+            - https://site.tld
+            ignored_patterns:
+            - host: "*.corp.tld"
+            ignored_globs:
+            - drafts/
+        "#;
+
+        let module: serde_yaml::Result<WintsModule> = serde_yaml::from_str(yaml);
+        print_serde_error_if_any(&module);
+        let module = module.unwrap();
+        assert_eq!(vec_of_strings!["https://site.tld"], module.ignored_urls());
+        assert_eq!(1, module.ignored_patterns().len());
+        assert_eq!(vec_of_strings!["drafts/"], module.ignored_globs());
+    }
+
     #[test]
     fn list_of_all_urls() {
         let module = some_testing_module();
@@ -235,6 +331,57 @@ mod tests {
         assert!(module.list_of_all_contexts().is_empty());
     }
 
+    #[test]
+    fn record_sources() {
+        let mut module = some_testing_module();
+        WintsModule::record_sources(
+            &mut module,
+            "https://test1.tld".to_string(),
+            vec_of_strings!["README.md:1:1", "docs/index.md:3:8"],
+        );
+        WintsModule::record_sources(
+            &mut module,
+            "https://test1.tld".to_string(),
+            vec_of_strings!["README.md:1:1"],
+        );
+        assert_eq!(
+            vec!["README.md:1:1", "docs/index.md:3:8"],
+            module.list_of_sources("https://test1.tld")
+        );
+        assert!(module.list_of_sources("https://test2.tld").is_empty());
+    }
+
+    #[test]
+    fn remove_urls() {
+        let mut module = some_testing_module();
+        WintsModule::record_sources(
+            &mut module,
+            "https://test1.tld".to_string(),
+            vec_of_strings!["README.md:1:1"],
+        );
+        let removed_urls = vec_of_strings![
+            "https://test1.tld",
+            "https://test2.tld",
+            "https://test3.tld"
+        ];
+        WintsModule::remove_urls(&mut module, &removed_urls);
+        assert_eq!(vec!["https://test4.tld"], module.list_of_all_urls());
+        assert_eq!(vec!["another terms"], module.list_of_all_contexts());
+        assert!(module.list_of_urls_with_sources().is_empty());
+    }
+
+    #[test]
+    fn list_of_contexts_with_url() {
+        let module = some_testing_module();
+        assert_eq!(
+            vec!["another terms"],
+            module.list_of_contexts_with_url("https://test3.tld")
+        );
+        assert!(module
+            .list_of_contexts_with_url("https://unknown.tld")
+            .is_empty());
+    }
+
     #[test]
     fn nearest_matching_context() {
         let module = some_testing_module();
@@ -1,25 +1,62 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
 use std::fs;
-use std::fs::{File, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::fs::File;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use serde::Serialize;
 
-use crate::core::config::WintsConfig;
+use crate::core::config::{ScanConfig, WintsConfig};
+use crate::core::forge::ForgeConfig;
+use crate::core::ignore_pattern::{IgnorePattern, IgnoreRule, UrlIgnoreList};
+use crate::core::journal::WintsJournal;
 use crate::core::module::WintsModule;
+use crate::core::template;
+use crate::core::template::WintsTemplate;
+use crate::util::config_dirs;
+use crate::util::file_changes::FileChange;
+use crate::util::file_store;
+use crate::util::url_normaliser::UrlNormaliser;
+use crate::util::yaml_edit;
 
 #[derive(Debug)]
 pub struct WintsStorage {
     local_basedir: PathBuf,
-    local_modules: HashMap<String, WintsModule>,
+    local_modules: BTreeMap<String, WintsModule>,
     local_ignore_path: PathBuf,
+    local_ignore_globs: Option<Vec<String>>,
     local_config: Option<WintsConfig>,
 
     global_basedir: PathBuf,
-    global_modules: HashMap<String, WintsModule>,
+    global_modules: BTreeMap<String, WintsModule>,
     global_ignore_path: PathBuf,
+    global_ignore_globs: Option<Vec<String>>,
     global_config: Option<WintsConfig>,
+
+    // Content of the files when loaded, to detect the ones changed by another process
+    loaded_files: HashMap<PathBuf, String>,
+    // Files of the modules, configs and ignore lists changed since loaded
+    dirty_files: BTreeSet<PathBuf>,
+    // Store only tells which files would be written
+    dry_run: bool,
+    // Command line recorded in the journal with the written files
+    command: String,
+}
+
+// Ignored URLs, patterns and globs of the local, global or module configuration
+pub struct IgnoreScope {
+    pub name: String,
+    pub config_path: PathBuf,
+    pub ignored_urls: Vec<String>,
+    pub ignored_patterns: Vec<IgnorePattern>,
+    pub ignore_globs: Vec<IgnoreGlob>,
+}
+
+pub struct IgnoreGlob {
+    pub glob: String,
+    // File declaring the glob
+    pub path: PathBuf,
 }
 
 impl WintsStorage {
@@ -28,11 +65,17 @@ impl WintsStorage {
             local_basedir: local_basedir.clone(),
             local_modules: Default::default(),
             local_ignore_path: local_basedir.join("ignore"),
+            local_ignore_globs: None,
             local_config: None,
             global_basedir: global_basedir.clone(),
             global_modules: Default::default(),
             global_ignore_path: global_basedir.join("ignore"),
+            global_ignore_globs: None,
             global_config: None,
+            loaded_files: Default::default(),
+            dirty_files: Default::default(),
+            dry_run: false,
+            command: WintsStorage::command_line(),
         };
 
         for path in glob::glob(
@@ -50,7 +93,7 @@ impl WintsStorage {
                 .to_os_string()
                 .into_string()
                 .unwrap();
-            let module = WintsStorage::load_module(&path)?;
+            let module = storage.load_module(&path)?;
             storage.local_modules.insert(module_name, module);
         }
 
@@ -69,55 +112,208 @@ impl WintsStorage {
                 .to_os_string()
                 .into_string()
                 .unwrap();
-            let module = WintsStorage::load_module(&path)?;
+            let module = storage.load_module(&path)?;
             storage.global_modules.insert(module_name, module);
         }
 
         let local_config_path = storage.local_basedir.join("options.yaml");
         if local_config_path.exists() {
-            storage.local_config = Some(WintsStorage::load_config(&local_config_path)?);
+            storage.local_config = Some(storage.load_config(&local_config_path)?);
         }
         let global_config_path = storage.global_basedir.join("options.yaml");
         if global_config_path.exists() {
-            storage.global_config = Some(WintsStorage::load_config(&global_config_path)?);
+            storage.global_config = Some(storage.load_config(&global_config_path)?);
         }
+        storage.local_ignore_globs =
+            storage.load_ignore_globs(&storage.local_ignore_path.clone())?;
+        storage.global_ignore_globs =
+            storage.load_ignore_globs(&storage.global_ignore_path.clone())?;
 
         Ok(storage)
     }
 
-    pub fn store(&self) -> Result<()> {
-        for local_module in &self.local_modules {
-            let path = self
-                .local_basedir
-                .join("modules")
-                .join(format!("{}.yaml", local_module.0));
-            WintsStorage::store_module(local_module.1, path)?;
+    // Load to change and store, nothing is written in dry-run mode
+    pub fn load_for_update(
+        local_basedir: PathBuf,
+        global_basedir: PathBuf,
+        dry_run: bool,
+    ) -> Result<WintsStorage> {
+        let mut storage = WintsStorage::load(local_basedir, global_basedir)?;
+        storage.dry_run = dry_run;
+        Ok(storage)
+    }
+
+    // Only the changed files are written and recorded in the journal,
+    // they are returned even in dry-run mode
+    pub fn store(&mut self) -> Result<Vec<FileChange>> {
+        let changes = self.changed_files()?;
+        if self.dry_run {
+            return Ok(changes);
         }
-        for global_module in &self.global_modules {
-            let path = self
-                .global_basedir
-                .join("modules")
-                .join(format!("{}.yaml", global_module.0));
-            WintsStorage::store_module(global_module.1, path)?;
+        let _locks = self.lock_folders_of(&changes)?;
+        // Nothing is written when a file has been changed by another process
+        for change in &changes {
+            if fs::read_to_string(&change.path).ok() != change.before {
+                return Err(anyhow!(
+                    "'{}' has been changed by another process since it was loaded, run the command again",
+                    change.path.display()
+                ));
+            }
         }
-        if let Some(local_config) = &self.local_config {
-            let path = self.local_basedir.join("options.yaml");
-            WintsStorage::store_config(local_config, path)?;
+        for change in &changes {
+            if let Some(after) = &change.after {
+                file_store::write_atomically(&change.path, after.as_bytes())?;
+                self.loaded_files.insert(change.path.clone(), after.clone());
+            }
         }
-        if let Some(global_config) = &self.global_config {
-            let path = self.global_basedir.join("options.yaml");
-            WintsStorage::store_config(global_config, path)?;
+        self.dirty_files.clear();
+        if !changes.is_empty() {
+            // Changes of the global configuration only are part of the global history
+            let journal = match changes
+                .iter()
+                .all(|change| change.path.starts_with(&self.global_basedir))
+            {
+                true => self.global_journal(),
+                false => self.journal(),
+            };
+            journal.record(self.command.clone(), &changes)?;
+        }
+        Ok(changes)
+    }
+
+    // Only the configuration folders holding changed files are locked, until the locks are dropped
+    pub fn lock_folders_of(&self, changes: &[FileChange]) -> Result<Vec<File>> {
+        let mut folders: Vec<PathBuf> = vec![];
+        for basedir in [&self.local_basedir, &self.global_basedir] {
+            if !changes
+                .iter()
+                .any(|change| change.path.starts_with(basedir))
+            {
+                continue;
+            }
+            fs::create_dir_all(basedir)?;
+            let folder = fs::canonicalize(basedir)?;
+            if !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        }
+        folders
+            .iter()
+            .map(|folder| file_store::lock_folder(folder))
+            .collect()
+    }
+
+    pub fn journal(&self) -> WintsJournal {
+        WintsJournal::new(self.local_basedir.join("journal"))
+    }
+
+    pub fn global_journal(&self) -> WintsJournal {
+        WintsJournal::new(config_dirs::global_state_dir(&self.global_basedir).join("journal"))
+    }
+
+    fn command_line() -> String {
+        env::args()
+            .skip(1)
+            .map(|arg| match arg.contains(char::is_whitespace) {
+                true => format!("'{}'", arg),
+                false => arg,
+            })
+            .fold("wints".to_string(), |line, arg| format!("{} {}", line, arg))
+    }
+
+    // Files which would be written by store, with their content before and after
+    pub fn changed_files(&self) -> Result<Vec<FileChange>> {
+        let mut changes: Vec<FileChange> = vec![];
+        for path in &self.dirty_files {
+            let content = match self.file_content(path)? {
+                Some(content) => content,
+                None => continue,
+            };
+            let before = self.loaded_files.get(path);
+            if before != Some(&content) {
+                changes.push(FileChange {
+                    path: path.clone(),
+                    before: before.cloned(),
+                    after: Some(content),
+                });
+            }
+        }
+        Ok(changes)
+    }
+
+    // Serialised content of a module, config or ignore list, none when it is not in the storage
+    fn file_content(&self, path: &Path) -> Result<Option<String>> {
+        for (is_global, modules) in [(false, &self.local_modules), (true, &self.global_modules)] {
+            for (module_name, module) in modules {
+                if self.module_path(module_name, is_global) == path {
+                    return Ok(Some(self.yaml_content(path, module)?));
+                }
+            }
+        }
+        for (is_global, config) in [(false, &self.local_config), (true, &self.global_config)] {
+            if let Some(config) = config {
+                if self.config_path(is_global) == path {
+                    return Ok(Some(self.yaml_content(path, config)?));
+                }
+            }
+        }
+        for (ignore_path, ignore_globs) in [
+            (&self.local_ignore_path, &self.local_ignore_globs),
+            (&self.global_ignore_path, &self.global_ignore_globs),
+        ] {
+            if let Some(ignore_globs) = ignore_globs {
+                if ignore_path == path {
+                    return Ok(Some(WintsStorage::ignore_globs_content(ignore_globs)));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    // The loaded file is edited in place to keep its comments and order, or written from scratch
+    fn yaml_content<T: Serialize>(&self, path: &Path, value: &T) -> Result<String> {
+        let edited = match self.loaded_files.get(path) {
+            Some(original) => yaml_edit::edit_yaml(original, &serde_yaml::to_value(value)?),
+            None => None,
+        };
+        match edited {
+            Some(edited) => Ok(edited),
+            None => Ok(serde_yaml::to_string(value)?),
+        }
+    }
+
+    fn module_path(&self, module_name: &str, is_global_module: bool) -> PathBuf {
+        let basedir = match is_global_module {
+            true => &self.global_basedir,
+            false => &self.local_basedir,
+        };
+        basedir
+            .join("modules")
+            .join(format!("{}.yaml", module_name))
+    }
+
+    fn config_path(&self, global_config: bool) -> PathBuf {
+        match global_config {
+            true => self.global_basedir.join("options.yaml"),
+            false => self.local_basedir.join("options.yaml"),
+        }
+    }
+
+    fn ignore_path(&self, global_config: bool) -> PathBuf {
+        match global_config {
+            true => self.global_ignore_path.clone(),
+            false => self.local_ignore_path.clone(),
         }
-        Ok(())
     }
 
     pub fn ignore_url(&mut self, url: String, global_config: bool) {
+        self.dirty_files.insert(self.config_path(global_config));
         match global_config {
             true => {
                 let global_config = match self.global_config.clone() {
                     None => WintsConfig {
-                        version: 1,
                         ignored_urls: Some(vec![url]),
+                        ..WintsConfig::empty_config()
                     },
                     Some(mut gc) => {
                         let mut ignored_urls = gc.ignored_urls.unwrap_or_default();
@@ -131,8 +327,8 @@ impl WintsStorage {
             false => {
                 let local_config = match self.local_config.clone() {
                     None => WintsConfig {
-                        version: 1,
                         ignored_urls: Some(vec![url]),
+                        ..WintsConfig::empty_config()
                     },
                     Some(mut lc) => {
                         let mut ignored_urls = lc.ignored_urls.unwrap_or_default();
@@ -146,19 +342,158 @@ impl WintsStorage {
         }
     }
 
-    pub fn ignore_glob(&self, glob: String, global_config: bool) -> Result<()> {
-        let ignore_path = match global_config {
-            true => self.global_ignore_path.clone(),
-            false => self.local_ignore_path.clone(),
+    pub fn ignore_pattern(&mut self, pattern: IgnorePattern, global_config: bool) {
+        self.dirty_files.insert(self.config_path(global_config));
+        let config = match global_config {
+            true => &mut self.global_config,
+            false => &mut self.local_config,
         };
-        fs::create_dir_all(ignore_path.parent().unwrap())?;
+        let mut updated_config = config.clone().unwrap_or_else(WintsConfig::empty_config);
+        let mut ignored_patterns = updated_config.ignored_patterns.unwrap_or_default();
+        ignored_patterns.push(pattern);
+        updated_config.ignored_patterns = Some(ignored_patterns);
+        *config = Some(updated_config);
+    }
 
-        let mut file = OpenOptions::new()
-            .append(true)
-            .create(true)
-            .open(ignore_path)?;
-        writeln!(file, "{}", glob)?;
-        Ok(())
+    pub fn ignore_glob(&mut self, glob: String, global_config: bool) {
+        self.dirty_files.insert(self.ignore_path(global_config));
+        let ignore_globs = match global_config {
+            true => &mut self.global_ignore_globs,
+            false => &mut self.local_ignore_globs,
+        };
+        ignore_globs.get_or_insert_with(Vec::new).push(glob);
+    }
+
+    // Remove the URL, and the patterns written the same way, return the number of removed entries
+    pub fn unignore_url(&mut self, url: &str, global_config: bool) -> usize {
+        self.dirty_files.insert(self.config_path(global_config));
+        let normaliser = self.url_normaliser();
+        let config = match global_config {
+            true => &mut self.global_config,
+            false => &mut self.local_config,
+        };
+        let config = match config {
+            None => return 0,
+            Some(config) => config,
+        };
+        let mut removed = 0;
+        if let Some(ignored_urls) = &mut config.ignored_urls {
+            let count = ignored_urls.len();
+            ignored_urls.retain(|ignored_url| !normaliser.same_url(ignored_url, url));
+            removed += count - ignored_urls.len();
+        }
+        if let Some(ignored_patterns) = &mut config.ignored_patterns {
+            let count = ignored_patterns.len();
+            ignored_patterns.retain(|pattern| match &pattern.rule {
+                IgnoreRule::Url(ignored_url) => !normaliser.same_url(ignored_url, url),
+                rule => rule.value() != url,
+            });
+            removed += count - ignored_patterns.len();
+            if ignored_patterns.is_empty() {
+                config.ignored_patterns = None;
+            }
+        }
+        removed
+    }
+
+    pub fn unignore_glob(&mut self, glob: &str, global_config: bool) -> bool {
+        self.dirty_files.insert(self.ignore_path(global_config));
+        let ignore_globs = match global_config {
+            true => &mut self.global_ignore_globs,
+            false => &mut self.local_ignore_globs,
+        };
+        match ignore_globs {
+            None => false,
+            Some(ignore_globs) => {
+                let count = ignore_globs.len();
+                ignore_globs.retain(|ignore_glob| ignore_glob.trim() != glob);
+                count != ignore_globs.len()
+            }
+        }
+    }
+
+    // Local and global scopes, then the scope of the module when there is one
+    pub fn ignore_scopes(&self, module_name: Option<String>) -> Vec<IgnoreScope> {
+        let mut scopes: Vec<IgnoreScope> = [
+            (
+                "local",
+                &self.local_basedir,
+                &self.local_ignore_path,
+                &self.local_config,
+                &self.local_ignore_globs,
+            ),
+            (
+                "global",
+                &self.global_basedir,
+                &self.global_ignore_path,
+                &self.global_config,
+                &self.global_ignore_globs,
+            ),
+        ]
+        .into_iter()
+        .map(
+            |(name, basedir, ignore_path, config, ignore_globs)| IgnoreScope {
+                name: name.to_string(),
+                config_path: basedir.join("options.yaml"),
+                ignored_urls: config
+                    .as_ref()
+                    .and_then(|config| config.ignored_urls.clone())
+                    .unwrap_or_default(),
+                ignored_patterns: config
+                    .as_ref()
+                    .and_then(|config| config.ignored_patterns.clone())
+                    .unwrap_or_default(),
+                ignore_globs: WintsStorage::ignore_globs_of(
+                    ignore_globs.iter().flatten().cloned().collect(),
+                    ignore_path,
+                ),
+            },
+        )
+        .collect();
+        if let Some(module_name) = module_name {
+            scopes.push(self.module_ignore_scope(module_name));
+        }
+        scopes
+    }
+
+    fn module_ignore_scope(&self, module_name: String) -> IgnoreScope {
+        let is_global_module = !self.local_modules.contains_key(&module_name)
+            && self.global_modules.contains_key(&module_name);
+        let module_path = self.module_path(&module_name, is_global_module);
+        let mut scope = IgnoreScope {
+            name: format!("module {}", module_name),
+            config_path: module_path.clone(),
+            ignored_urls: vec![],
+            ignored_patterns: vec![],
+            ignore_globs: vec![],
+        };
+        if let Some(module) = self.find_module(module_name.clone()) {
+            scope.ignored_urls = module.ignored_urls();
+            scope.ignored_patterns = module.ignored_patterns();
+            scope.ignore_globs =
+                WintsStorage::ignore_globs_of(module.ignored_globs(), &module_path);
+        }
+        for ignore_path in self.list_of_module_ignore_files(&module_name) {
+            let ignore_globs: Vec<String> = fs::read_to_string(&ignore_path)
+                .map(|content| content.lines().map(str::to_string).collect())
+                .unwrap_or_default();
+            scope
+                .ignore_globs
+                .extend(WintsStorage::ignore_globs_of(ignore_globs, &ignore_path));
+        }
+        scope
+    }
+
+    fn ignore_globs_of(lines: Vec<String>, path: &Path) -> Vec<IgnoreGlob> {
+        lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| IgnoreGlob {
+                glob: line.to_string(),
+                path: path.to_path_buf(),
+            })
+            .collect()
     }
 
     pub fn contextualise_urls(
@@ -175,6 +510,43 @@ impl WintsStorage {
         WintsModule::contextualise_urls(module, context.to_string(), urls);
     }
 
+    pub fn record_sources(
+        &mut self,
+        module_name: String,
+        is_global_module: bool,
+        url: String,
+        sources: Vec<String>,
+    ) {
+        let module = match self.find_module_mut(module_name.clone(), is_global_module) {
+            None => self.new_module(module_name, is_global_module),
+            Some(module_mut) => module_mut,
+        };
+        WintsModule::record_sources(module, url, sources);
+    }
+
+    pub fn remove_urls(&mut self, module_name: String, is_global_module: bool, urls: &[String]) {
+        if let Some(module) = self.find_module_mut(module_name, is_global_module) {
+            WintsModule::remove_urls(module, urls);
+        }
+    }
+
+    // URLs of a context of the local or the global module
+    pub fn list_of_context_urls(
+        &self,
+        module_name: &str,
+        is_global_module: bool,
+        context: &str,
+    ) -> Vec<String> {
+        let modules = match is_global_module {
+            true => &self.global_modules,
+            false => &self.local_modules,
+        };
+        modules
+            .get(module_name)
+            .map(|module| module.list_of_context_urls(context))
+            .unwrap_or_default()
+    }
+
     pub fn list_of_all_urls(&self, module_name: String) -> Vec<String> {
         match self.find_module(module_name) {
             None => vec![],
@@ -205,11 +577,100 @@ impl WintsStorage {
         ignored_urls
     }
 
-    pub fn list_of_ignore_files(&self) -> Vec<PathBuf> {
-        vec![
+    pub fn ignored_patterns(&self) -> Vec<IgnorePattern> {
+        [&self.local_config, &self.global_config]
+            .into_iter()
+            .flatten()
+            .flat_map(|config| config.ignored_patterns.clone().unwrap_or_default())
+            .collect()
+    }
+
+    // Global, local and module-specific rules combined
+    pub fn url_ignore_list(&self, module_name: String) -> UrlIgnoreList {
+        let mut ignored_urls = self.ignored_urls();
+        let mut ignored_patterns = self.ignored_patterns();
+        if let Some(module) = self.find_module(module_name) {
+            ignored_urls.extend(module.ignored_urls());
+            ignored_patterns.extend(module.ignored_patterns());
+        }
+        UrlIgnoreList::new(self.url_normaliser(), ignored_urls, ignored_patterns)
+    }
+
+    pub fn scan_config(&self) -> ScanConfig {
+        ScanConfig::merge(
+            self.local_config.clone().and_then(|config| config.scan),
+            self.global_config.clone().and_then(|config| config.scan),
+        )
+    }
+
+    // Local tracking parameters take precedence over the global ones
+    pub fn url_normaliser(&self) -> UrlNormaliser {
+        [&self.local_config, &self.global_config]
+            .into_iter()
+            .flatten()
+            .find_map(|config| config.tracking_params.clone())
+            .map(UrlNormaliser::new)
+            .unwrap_or_default()
+    }
+
+    // Local forge layouts take precedence over the global ones
+    pub fn forge_config(&self, host: &str) -> Option<ForgeConfig> {
+        [&self.local_config, &self.global_config]
+            .into_iter()
+            .flatten()
+            .find_map(|config| config.forges.as_ref()?.get(host).cloned())
+    }
+
+    // Local templates take precedence over the global ones
+    pub fn list_of_templates(&self) -> Result<Vec<WintsTemplate>> {
+        template::load_templates(&[
+            self.local_basedir.join("templates"),
+            self.global_basedir.join("templates"),
+        ])
+    }
+
+    // Folder holding the local configuration, the sources of the scanned URLs are relative to it
+    pub fn project_root(&self) -> PathBuf {
+        let local_basedir = file_store::resolve_path(&self.local_basedir);
+        match local_basedir.parent() {
+            Some(project_root) => project_root.to_path_buf(),
+            None => local_basedir,
+        }
+    }
+
+    pub fn scan_cache_path(&self) -> PathBuf {
+        self.local_basedir.join("cache").join("scan.json")
+    }
+
+    pub fn list_of_ignore_files(&self, module_name: String) -> Vec<PathBuf> {
+        let mut ignore_files = vec![
             self.local_ignore_path.clone(),
             self.global_ignore_path.clone(),
-        ]
+        ];
+        ignore_files.extend(self.list_of_module_ignore_files(&module_name));
+        ignore_files
+    }
+
+    // Ignore globs declared inside the module file
+    pub fn list_of_module_ignore_globs(&self, module_name: String) -> Vec<String> {
+        self.find_module(module_name)
+            .map(|module| module.ignored_globs())
+            .unwrap_or_default()
+    }
+
+    fn list_of_module_ignore_files(&self, module_name: &str) -> Vec<PathBuf> {
+        [&self.local_basedir, &self.global_basedir]
+            .into_iter()
+            .map(|basedir| basedir.join(format!("ignore.{}", module_name)))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    pub fn has_module(&self, module_name: &str, is_global_module: bool) -> bool {
+        match is_global_module {
+            true => self.global_modules.contains_key(module_name),
+            false => self.local_modules.contains_key(module_name),
+        }
     }
 
     pub fn find_module(&self, module_name: String) -> Option<&WintsModule> {
@@ -218,46 +679,56 @@ impl WintsStorage {
             .or_else(move || self.global_modules.get(&module_name))
     }
 
-    fn store_config(config: &WintsConfig, path: PathBuf) -> Result<()> {
-        fs::create_dir_all(path.parent().unwrap())?;
-        let mut file = File::create(&path)?;
-        let config_content = serde_yaml::to_string(&config)?;
-        file.write_all(config_content.as_bytes())?;
-        Ok(())
+    fn ignore_globs_content(ignore_globs: &[String]) -> String {
+        ignore_globs
+            .iter()
+            .map(|glob| format!("{}\n", glob))
+            .collect()
     }
 
-    fn store_module(module: &WintsModule, path: PathBuf) -> Result<()> {
-        fs::create_dir_all(path.parent().unwrap())?;
-        let mut file = File::create(&path)?;
-        let module_content = serde_yaml::to_string(&module)?;
-        file.write_all(module_content.as_bytes())?;
-        Ok(())
+    fn load_ignore_globs(&mut self, path: &Path) -> Result<Option<Vec<String>>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = self.read_file(path)?;
+        Ok(Some(content.lines().map(str::to_string).collect()))
     }
 
-    fn load_config(path: &PathBuf) -> Result<WintsConfig> {
-        let file = File::open(path)?;
-        let config: WintsConfig = serde_yaml::from_reader(file)?;
+    fn load_config(&mut self, path: &Path) -> Result<WintsConfig> {
+        let content = self.read_file(path)?;
+        let config: WintsConfig = serde_yaml::from_str(&content)?;
         Ok(config)
     }
 
-    fn load_module(path: &PathBuf) -> Result<WintsModule> {
-        let file = File::open(path)?;
-        let module: WintsModule = serde_yaml::from_reader(file)?;
+    fn load_module(&mut self, path: &Path) -> Result<WintsModule> {
+        let content = self.read_file(path)?;
+        let module: WintsModule = serde_yaml::from_str(&content)?;
         Ok(module)
     }
 
+    fn read_file(&mut self, path: &Path) -> Result<String> {
+        let content = fs::read_to_string(path)?;
+        self.loaded_files
+            .insert(path.to_path_buf(), content.clone());
+        Ok(content)
+    }
+
+    // The local module, or the global one when there is no local module of this name,
+    // its file is written by the next store
     fn find_module_mut(
         &mut self,
         module_name: String,
         is_global_module: bool,
     ) -> Option<&mut WintsModule> {
-        let lm = &mut self.local_modules;
-        let gm = &mut self.global_modules;
+        let is_global_module = is_global_module || !self.local_modules.contains_key(&module_name);
+        if is_global_module && !self.global_modules.contains_key(&module_name) {
+            return None;
+        }
+        self.dirty_files
+            .insert(self.module_path(&module_name, is_global_module));
         match is_global_module {
-            true => gm.get_mut(&module_name),
-            false => lm
-                .get_mut(&module_name)
-                .or_else(move || gm.get_mut(&module_name)),
+            true => self.global_modules.get_mut(&module_name),
+            false => self.local_modules.get_mut(&module_name),
         }
     }
 
@@ -268,9 +739,115 @@ impl WintsStorage {
     }
 
     pub fn add_module(&mut self, module_name: String, module: WintsModule, is_global_module: bool) {
+        self.dirty_files
+            .insert(self.module_path(&module_name, is_global_module));
         match is_global_module {
             true => self.global_modules.insert(module_name, module),
             false => self.local_modules.insert(module_name, module),
         };
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::core::storage::*;
+
+    fn add_url(storage: &mut WintsStorage, module_name: &str, url: &str) {
+        storage.contextualise_urls(
+            module_name.to_string(),
+            false,
+            "docs",
+            vec![url.to_string()],
+        );
+    }
+
+    #[test]
+    fn store_only_changed_files() -> Result<()> {
+        let local = tempdir()?;
+        let global = tempdir()?;
+        let mut storage = WintsStorage::load_for_update(
+            local.path().to_path_buf(),
+            global.path().to_path_buf(),
+            false,
+        )?;
+        add_url(&mut storage, "main", "https://docs.rs");
+        add_url(&mut storage, "other", "https://crates.io");
+        assert_eq!(2, storage.store()?.len());
+        assert!(storage.store()?.is_empty());
+
+        add_url(&mut storage, "other", "https://lib.rs");
+        storage.remove_urls("main".to_string(), false, &[]);
+        let changes = storage.store()?;
+        assert_eq!(1, changes.len());
+        assert_eq!(
+            local.path().join("modules").join("other.yaml"),
+            changes[0].path
+        );
+        assert!(changes[0].before.is_some());
+        assert_eq!(2, storage.journal().entries()?.len());
+
+        storage.ignore_glob("target".to_string(), true);
+        storage.store()?;
+        assert_eq!(2, storage.journal().entries()?.len());
+        assert_eq!(1, storage.global_journal().entries()?.len());
+        assert!(global.path().join("journal").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn store_in_dry_run_mode() -> Result<()> {
+        let local = tempdir()?;
+        let global = tempdir()?;
+        let mut storage = WintsStorage::load_for_update(
+            local.path().to_path_buf(),
+            global.path().to_path_buf(),
+            true,
+        )?;
+        add_url(&mut storage, "main", "https://docs.rs");
+        storage.ignore_glob("target".to_string(), true);
+        let mut expected = vec![
+            global.path().join("ignore"),
+            local.path().join("modules").join("main.yaml"),
+        ];
+        expected.sort();
+        let changes = storage.store()?;
+        let paths: Vec<PathBuf> = changes.iter().map(|change| change.path.clone()).collect();
+        assert_eq!(expected, paths);
+        let ignore_change = changes
+            .iter()
+            .find(|change| change.path == global.path().join("ignore"))
+            .unwrap();
+        assert_eq!(Some("target\n".to_string()), ignore_change.after);
+        assert_eq!(None, ignore_change.before);
+        assert!(!local.path().join("modules").exists());
+        assert!(!global.path().join("ignore").exists());
+        assert!(!local.path().join("journal").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn store_a_file_changed_since_loaded() -> Result<()> {
+        let local = tempdir()?;
+        let global = tempdir()?;
+        let mut storage = WintsStorage::load_for_update(
+            local.path().to_path_buf(),
+            global.path().to_path_buf(),
+            false,
+        )?;
+        add_url(&mut storage, "main", "https://docs.rs");
+        storage.store()?;
+
+        fs::write(
+            local.path().join("modules").join("main.yaml"),
+            "version: 1\nelements: []\n",
+        )?;
+        add_url(&mut storage, "main", "https://crates.io");
+        assert!(storage.store().is_err());
+        Ok(())
+    }
+}
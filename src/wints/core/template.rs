@@ -0,0 +1,309 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value};
+
+use crate::core::module::WintsModule;
+
+// Templates which are not read from a templates folder, they win over a file of the same name
+pub const BUILTIN_TEMPLATES: [(&str, &str); 3] = [
+    ("empty", "Module without URLs"),
+    ("default", "URLs of the wints project"),
+    (
+        "git",
+        "Pages of the repository on its forge, from its git remote",
+    ),
+];
+
+// Replaced by the value of the variable, like '{{service}}'
+const PLACEHOLDER_PATTERN: &str = r"\{\{\s*([A-Za-z0-9_-]+)\s*\}\}";
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TemplateVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+}
+
+// A module file with placeholders, and optionally its description and variables
+#[derive(Clone, Debug)]
+pub struct WintsTemplate {
+    pub name: String,
+    pub path: PathBuf,
+    pub description: Option<String>,
+    pub variables: Vec<TemplateVariable>,
+    module: Value,
+}
+
+impl WintsTemplate {
+    pub fn load(path: &Path) -> Result<WintsTemplate> {
+        let name = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .ok_or_else(|| anyhow!("invalid template '{}'", path.display()))?;
+        let content = fs::read_to_string(path)?;
+        let invalid = |err: String| anyhow!("invalid template '{}': {}", path.display(), err);
+        let mut module = match serde_yaml::from_str::<Value>(&content) {
+            Ok(Value::Mapping(mapping)) => mapping,
+            Ok(Value::Null) => Mapping::new(),
+            Ok(_) => return Err(invalid("expected a mapping".to_string())),
+            Err(err) => return Err(invalid(err.to_string())),
+        };
+        let description = match module.remove("description") {
+            Some(description) => {
+                serde_yaml::from_value(description).map_err(|err| invalid(err.to_string()))?
+            }
+            None => None,
+        };
+        let mut variables: Vec<TemplateVariable> = match module.remove("variables") {
+            Some(variables) => {
+                serde_yaml::from_value(variables).map_err(|err| invalid(err.to_string()))?
+            }
+            None => vec![],
+        };
+        if !module.contains_key("version") {
+            module.insert("version".into(), 1.into());
+        }
+        if !module.contains_key("elements") {
+            module.insert("elements".into(), Value::Sequence(vec![]));
+        }
+        let module = Value::Mapping(module);
+
+        // Placeholders not declared as variables are variables without description
+        let mut placeholders = vec![];
+        collect_placeholders(&module, &mut placeholders);
+        for placeholder in placeholders {
+            if !variables
+                .iter()
+                .any(|variable| variable.name == placeholder)
+            {
+                variables.push(TemplateVariable {
+                    name: placeholder,
+                    description: None,
+                    default: None,
+                });
+            }
+        }
+
+        let template = WintsTemplate {
+            name,
+            path: path.to_path_buf(),
+            description,
+            variables,
+            module,
+        };
+        template.render(&template.sample_values())?;
+        Ok(template)
+    }
+
+    // Module with each placeholder replaced by its value
+    pub fn render(&self, values: &BTreeMap<String, String>) -> Result<WintsModule> {
+        let mut module = self.module.clone();
+        replace_placeholders(&mut module, values)?;
+        serde_yaml::from_value(module)
+            .map_err(|err| anyhow!("invalid template '{}': {}", self.path.display(), err))
+    }
+
+    fn sample_values(&self) -> BTreeMap<String, String> {
+        self.variables
+            .iter()
+            .map(|variable| (variable.name.clone(), variable.name.clone()))
+            .collect()
+    }
+}
+
+// Templates of the folders, a template of the first folders wins over one of the same name
+pub fn load_templates(folders: &[PathBuf]) -> Result<Vec<WintsTemplate>> {
+    let mut templates: Vec<WintsTemplate> = vec![];
+    for folder in folders {
+        let mut paths: Vec<PathBuf> = glob::glob(format!("{}/*.yaml", folder.display()).as_str())?
+            .flatten()
+            .collect();
+        paths.sort();
+        for path in paths {
+            let template = WintsTemplate::load(&path)?;
+            if !templates.iter().any(|known| known.name == template.name) {
+                templates.push(template);
+            }
+        }
+    }
+    Ok(templates)
+}
+
+pub fn is_builtin_template(name: &str) -> bool {
+    BUILTIN_TEMPLATES
+        .iter()
+        .any(|(builtin_name, _)| *builtin_name == name)
+}
+
+// 'key=value' arguments, the last value of a key is kept
+pub fn parse_variables(arguments: &[String]) -> Result<BTreeMap<String, String>> {
+    arguments
+        .iter()
+        .map(|argument| match argument.split_once('=') {
+            Some((key, value)) if !key.trim().is_empty() => {
+                Ok((key.trim().to_string(), value.to_string()))
+            }
+            _ => Err(anyhow!(
+                "invalid variable '{}', expected KEY=VALUE",
+                argument
+            )),
+        })
+        .collect()
+}
+
+fn collect_placeholders(value: &Value, placeholders: &mut Vec<String>) {
+    match value {
+        Value::String(text) => {
+            for captures in Regex::new(PLACEHOLDER_PATTERN).unwrap().captures_iter(text) {
+                let name = captures[1].to_string();
+                if !placeholders.contains(&name) {
+                    placeholders.push(name);
+                }
+            }
+        }
+        Value::Sequence(values) => values
+            .iter()
+            .for_each(|value| collect_placeholders(value, placeholders)),
+        Value::Mapping(mapping) => mapping
+            .values()
+            .for_each(|value| collect_placeholders(value, placeholders)),
+        _ => {}
+    }
+}
+
+fn replace_placeholders(value: &mut Value, values: &BTreeMap<String, String>) -> Result<()> {
+    match value {
+        Value::String(text) => {
+            let pattern = Regex::new(PLACEHOLDER_PATTERN).unwrap();
+            if let Some(captures) = pattern
+                .captures_iter(text)
+                .find(|captures| !values.contains_key(&captures[1]))
+            {
+                return Err(anyhow!("missing value for variable '{}'", &captures[1]));
+            }
+            *text = pattern
+                .replace_all(text, |captures: &regex::Captures| {
+                    values[&captures[1]].clone()
+                })
+                .to_string();
+        }
+        Value::Sequence(items) => {
+            for item in items {
+                replace_placeholders(item, values)?;
+            }
+        }
+        Value::Mapping(mapping) => {
+            for (_, item) in mapping.iter_mut() {
+                replace_placeholders(item, values)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::core::template::*;
+
+    #[test]
+    fn render_a_template() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("service.yaml");
+        fs::write(
+            &path,
+            r#"description: Standard service
+variables:
+  - name: service
+    description: Name of the service
+  - name: team
+    default: platform
+elements:
+  - context: "{{service}} docs"
+    urls:
+      - https://docs.corp.tld/{{ team }}/{{service}}
+  - context: "{{service}} dashboard"
+    urls:
+      - https://grafana.corp.tld/d/{{service}}?env={{env}}
+"#,
+        )?;
+
+        let template = WintsTemplate::load(&path)?;
+        assert_eq!("service", template.name);
+        assert_eq!(Some("Standard service".to_string()), template.description);
+        let names: Vec<&str> = template
+            .variables
+            .iter()
+            .map(|variable| variable.name.as_str())
+            .collect();
+        assert_eq!(vec!["service", "team", "env"], names);
+
+        let values: BTreeMap<String, String> = parse_variables(&[
+            "service=billing".to_string(),
+            "team=payments".to_string(),
+            "env=prod".to_string(),
+        ])?;
+        let module = template.render(&values)?;
+        assert_eq!(
+            vec!["billing docs", "billing dashboard"],
+            module.list_of_all_contexts()
+        );
+        assert_eq!(
+            vec![
+                "https://docs.corp.tld/payments/billing",
+                "https://grafana.corp.tld/d/billing?env=prod"
+            ],
+            module.list_of_all_urls()
+        );
+
+        let mut missing = values.clone();
+        missing.remove("env");
+        assert!(template.render(&missing).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn local_templates_win() -> Result<()> {
+        let local = tempdir()?;
+        let global = tempdir()?;
+        fs::write(local.path().join("service.yaml"), "description: Local")?;
+        fs::write(global.path().join("service.yaml"), "description: Global")?;
+        fs::write(global.path().join("team.yaml"), "")?;
+
+        let templates = load_templates(&[
+            local.path().to_path_buf(),
+            global.path().to_path_buf(),
+            local.path().join("missing"),
+        ])?;
+        assert_eq!(2, templates.len());
+        assert_eq!(Some("Local".to_string()), templates[0].description);
+        assert_eq!("team", templates[1].name);
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_templates() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("broken.yaml");
+        fs::write(&path, "elements: 12")?;
+        assert!(WintsTemplate::load(&path).is_err());
+        fs::write(&path, "- not a mapping")?;
+        assert!(WintsTemplate::load(&path).is_err());
+        assert!(parse_variables(&["service".to_string()]).is_err());
+        assert!(parse_variables(&["=billing".to_string()]).is_err());
+        Ok(())
+    }
+}
@@ -1,6 +1,17 @@
 pub mod wints_add;
 pub mod wints_init;
+pub mod wints_log;
+pub mod wints_migrate;
+pub mod wints_prune;
+pub mod wints_redo;
 pub mod wints_scan;
 pub mod wints_search;
+pub mod wints_undo;
 pub mod wints_url_ignore;
 pub mod wints_url_ignore_glob;
+pub mod wints_url_ignored;
+pub mod wints_url_unignore;
+pub mod wints_url_unignore_glob;
+pub mod wints_url_why;
+pub mod wints_where;
+pub mod wints_which;
@@ -3,7 +3,11 @@ use std::path::PathBuf;
 use anyhow::Result;
 
 use crate::core::storage::WintsStorage;
-use crate::util::log::{DRY_RUN, WRITE};
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::log;
+use crate::util::log::{DRY_RUN, INFO, WRITE};
 
 pub struct AddOptions {
     pub local_basedir: PathBuf,
@@ -13,42 +17,74 @@ pub struct AddOptions {
     pub url: String,
     pub context: String,
     pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
 }
 
 pub fn add(options: AddOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
     if options.dry_run {
-        println!(" {} Dry-run mode activated.", DRY_RUN);
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
     }
 
-    println!(
+    log_line!(
         " {} Add '{}' to '{}' in '{}'...",
-        WRITE, options.url, options.context, options.module_name,
+        WRITE,
+        options.url,
+        options.context,
+        options.module_name,
     );
 
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+    // The URL is stored as given, its canonical form is only used to find it in the context
+    let normaliser = storage.url_normaliser();
+    let url = options.url.clone();
+    if storage
+        .list_of_context_urls(
+            &options.module_name,
+            options.global_module,
+            &options.context,
+        )
+        .iter()
+        .any(|known_url| normaliser.same_url(known_url, &url))
+    {
+        log_line!(
+            " {} '{}' is already in '{}' in '{}'",
+            INFO,
+            url,
+            options.context,
+            options.module_name
+        );
+        return Ok(());
+    }
+    storage.contextualise_urls(
+        options.module_name.clone(),
+        options.global_module,
+        options.context.as_str(),
+        vec![url.clone()],
+    );
+    let changed_files = storage.store()?;
     match options.dry_run {
         true => {
-            println!(
+            log_line!(
                 " {} Add '{}' to '{}' in '{}'",
-                DRY_RUN, options.url, options.context, options.module_name
-            );
-        }
-        false => {
-            let mut storage = WintsStorage::load(
-                options.local_basedir.clone(),
-                options.global_basedir.clone(),
-            )?;
-            storage.contextualise_urls(
-                options.module_name.clone(),
-                options.global_module,
-                options.context.as_str(),
-                vec![options.url.clone()],
-            );
-            storage.store()?;
-            println!(
-                " {} '{}' have been added to '{}' in '{}'",
-                WRITE, options.url, options.context, options.module_name
+                DRY_RUN,
+                url,
+                options.context,
+                options.module_name
             );
+            file_changes::report_changes(&changed_files, options.dry_run_format)?;
         }
+        false => log_line!(
+            " {} '{}' have been added to '{}' in '{}'",
+            WRITE,
+            url,
+            options.context,
+            options.module_name
+        ),
     }
 
     Ok(())
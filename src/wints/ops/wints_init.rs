@@ -1,11 +1,22 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::io::{IsTerminal, Write};
 use std::path::PathBuf;
 use std::process;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
+use crate::core::forge;
 use crate::core::module::WintsModule;
 use crate::core::storage::WintsStorage;
-use crate::util::log::{CAUTION, DRY_RUN, WRITE};
+use crate::core::template;
+use crate::core::template::{TemplateVariable, WintsTemplate, BUILTIN_TEMPLATES};
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::git_remote;
+use crate::util::log;
+use crate::util::log::{ASK, CAUTION, DRY_RUN, INFO, WRITE};
 
 pub struct InitOptions {
     pub local_basedir: PathBuf,
@@ -13,53 +24,85 @@ pub struct InitOptions {
     pub module_name: String,
     pub global_module: bool,
     pub template: String,
+    // Repository used by the 'git' template
+    pub repository: PathBuf,
+    // Values of the template variables, as 'key=value'
+    pub variables: Vec<String>,
+    pub list_templates: bool,
+    // Overwrite an existing module
+    pub force: bool,
     pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
 }
 
 pub fn init(options: InitOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
+    if options.list_templates {
+        return list_templates(&options);
+    }
     if options.dry_run {
-        println!(" {} Dry-run mode activated.", DRY_RUN);
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
     }
 
-    println!(
+    log_line!(
         " {} Initialise '{}' with template '{}'...",
-        WRITE, options.module_name, options.template
+        WRITE,
+        options.module_name,
+        options.template
     );
 
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+    if !options.force && storage.has_module(&options.module_name, options.global_module) {
+        return Err(anyhow!(
+            "the module '{}' already exists, use --force to overwrite it",
+            options.module_name
+        ));
+    }
+
     let module = match options.template.as_str() {
         "empty" => WintsModule::empty_module(),
         "default" => WintsModule::default_module(),
-        _ => {
-            println!(
-                " {} Need a valid template : '{}' is unknown",
-                CAUTION, options.template
-            );
-            process::exit(1);
-        }
+        "git" => git_module(&options, &storage)?,
+        name => match storage
+            .list_of_templates()?
+            .into_iter()
+            .find(|template| template.name == name)
+        {
+            Some(template) => template_module(&options, &template)?,
+            None => {
+                log_line!(
+                    " {} Need a valid template : '{}' is unknown",
+                    CAUTION,
+                    options.template
+                );
+                process::exit(1);
+            }
+        },
     };
 
+    storage.add_module(
+        options.module_name.clone(),
+        module.clone(),
+        options.global_module,
+    );
+    let changed_files = storage.store()?;
     match options.dry_run {
         true => {
-            println!(
+            log_line!(
                 " {} Add '{}' module with {} contexts and {} URLs",
                 DRY_RUN,
                 options.module_name,
                 &module.list_of_all_contexts().len(),
                 &module.list_of_all_urls().len()
             );
+            file_changes::report_changes(&changed_files, options.dry_run_format)?;
         }
         false => {
-            let mut storage = WintsStorage::load(
-                options.local_basedir.clone(),
-                options.global_basedir.clone(),
-            )?;
-            storage.add_module(
-                options.module_name.clone(),
-                module.clone(),
-                options.global_module,
-            );
-            storage.store()?;
-            println!(
+            log_line!(
                 " {} A new '{}' module have been created with {} contexts and {} URLs",
                 WRITE,
                 options.module_name,
@@ -71,3 +114,146 @@ pub fn init(options: InitOptions) -> Result<()> {
 
     Ok(())
 }
+
+// Pages of the repository on its forge, from the 'origin' remote
+fn git_module(options: &InitOptions, storage: &WintsStorage) -> Result<WintsModule> {
+    let remotes = git_remote::read_remotes(&options.repository)?;
+    let remote = git_remote::preferred_remote(&remotes)
+        .ok_or_else(|| anyhow!("no git remote in '{}'", options.repository.display()))?;
+    let location = git_remote::parse_remote_url(&remote.url).ok_or_else(|| {
+        anyhow!(
+            "the remote '{}' is not hosted on a forge: {}",
+            remote.name,
+            remote.url
+        )
+    })?;
+    log_line!(
+        " {} Using the remote '{}' ({}/{})",
+        INFO,
+        remote.name,
+        location.host,
+        location.path
+    );
+
+    let config = storage.forge_config(&location.host);
+    if !forge::is_known_forge(&location, config.as_ref()) {
+        log_line!(
+            " {} Unknown forge '{}', only its code URL is generated. Set its layout in the 'forges' of the options.",
+            CAUTION, location.host
+        );
+    }
+    let mut module = WintsModule::empty_module();
+    for (context, url) in forge::repository_pages(&location, config.as_ref()) {
+        WintsModule::contextualise_urls(&mut module, context, vec![url]);
+    }
+    Ok(module)
+}
+
+// Variables not given on the command line are asked, or take their default value
+fn template_module(options: &InitOptions, template: &WintsTemplate) -> Result<WintsModule> {
+    let mut values = template::parse_variables(&options.variables)?;
+    for name in values.keys() {
+        if !template
+            .variables
+            .iter()
+            .any(|variable| variable.name == *name)
+        {
+            log_line!(
+                " {} The variable '{}' is not used by the template '{}'",
+                CAUTION,
+                name,
+                template.name
+            );
+        }
+    }
+    for variable in &template.variables {
+        if values.contains_key(&variable.name) {
+            continue;
+        }
+        let value = match io::stdin().is_terminal() {
+            true => ask_variable(variable)?,
+            false => variable.default.clone(),
+        }
+        .ok_or_else(|| {
+            anyhow!(
+                "missing value for variable '{}', set it with --var {}=VALUE",
+                variable.name,
+                variable.name
+            )
+        })?;
+        values.insert(variable.name.clone(), value);
+    }
+    template.render(&values)
+}
+
+fn ask_variable(variable: &TemplateVariable) -> Result<Option<String>> {
+    let mut question = format!(" {} {}", ASK, variable.name);
+    if let Some(description) = &variable.description {
+        question.push_str(&format!(" ({})", description));
+    }
+    if let Some(default) = &variable.default {
+        question.push_str(&format!(" [{}]", default));
+    }
+    match log::logs_to_stderr() {
+        true => {
+            eprint!("{}: ", question);
+            io::stderr().flush()?;
+        }
+        false => {
+            print!("{}: ", question);
+            io::stdout().flush()?;
+        }
+    }
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    match answer.trim() {
+        "" => Ok(variable.default.clone()),
+        answer => Ok(Some(answer.to_string())),
+    }
+}
+
+// Built-in templates, then the local and global ones
+fn list_templates(options: &InitOptions) -> Result<()> {
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+
+    let mut entries: Vec<String> = BUILTIN_TEMPLATES
+        .iter()
+        .map(|(name, description)| format!("[built-in] {}, {}", name, description))
+        .collect();
+    let mut templates: BTreeMap<String, String> = BTreeMap::new();
+    for template in storage.list_of_templates()? {
+        if template::is_builtin_template(&template.name) {
+            log_line!(
+                " {} The template '{}' is hidden by the built-in one",
+                CAUTION,
+                template.path.display()
+            );
+            continue;
+        }
+        let scope = match template.path.starts_with(&options.local_basedir) {
+            true => "local",
+            false => "global",
+        };
+        let mut entry = format!("[{}] {}", scope, template.name);
+        if let Some(description) = &template.description {
+            entry.push_str(&format!(", {}", description));
+        }
+        if !template.variables.is_empty() {
+            let names: Vec<&str> = template
+                .variables
+                .iter()
+                .map(|variable| variable.name.as_str())
+                .collect();
+            entry.push_str(&format!(" (variables: {})", names.join(", ")));
+        }
+        templates.insert(template.name.clone(), entry);
+    }
+    entries.extend(templates.into_values());
+
+    log_line!(" {} {} templates:", INFO, entries.len());
+    entries.iter().for_each(|entry| log_line!("    {}", entry));
+    Ok(())
+}
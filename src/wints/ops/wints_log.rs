@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::core::storage::WintsStorage;
+use crate::util::log::{INFO, SAD};
+
+pub struct LogOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    // Use the journal of the commands changing the global configuration only
+    pub global: bool,
+}
+
+// Journal entries from the newest to the oldest
+pub fn log(options: LogOptions) -> Result<()> {
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+
+    let journal = match options.global {
+        true => storage.global_journal(),
+        false => storage.journal(),
+    };
+    let entries = journal.entries()?;
+    match entries.is_empty() {
+        true => println!(" {} The journal is empty.", SAD),
+        false => {
+            println!(" {} {} journal entries:", INFO, entries.len());
+            for entry in entries.iter().rev() {
+                let mut line = format!(
+                    "    #{} {} {} ({} files)",
+                    entry.id,
+                    entry.date,
+                    entry.command,
+                    entry.files.len()
+                );
+                if entry.undone {
+                    line.push_str(" [undone]");
+                }
+                println!("{}", line);
+                for file in &entry.files {
+                    println!("        {} '{}'", file.status(), file.path.display());
+                }
+            }
+        }
+    }
+    Ok(())
+}
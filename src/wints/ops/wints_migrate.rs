@@ -0,0 +1,126 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+
+use crate::core::journal::WintsJournal;
+use crate::util::config_dirs;
+use crate::util::config_dirs::{GlobalConfigDir, GlobalConfigOrigin};
+use crate::util::file_store;
+use crate::util::log::{DRY_RUN, SAD, WRITE};
+
+pub struct MigrateOptions {
+    pub global_config_dir: GlobalConfigDir,
+    pub dry_run: bool,
+}
+
+// Move HOME/.wints to the XDG folders, or to the global configuration folder when it is set
+pub fn migrate(options: MigrateOptions) -> Result<()> {
+    if options.dry_run {
+        println!(" {} Dry-run mode activated.", DRY_RUN);
+    }
+
+    let legacy_dir = config_dirs::legacy_global_dir();
+    let config_dir = match options.global_config_dir.origin {
+        GlobalConfigOrigin::Option | GlobalConfigOrigin::Environment => {
+            options.global_config_dir.path.clone()
+        }
+        GlobalConfigOrigin::Legacy | GlobalConfigOrigin::Xdg => config_dirs::xdg_config_dir(),
+    };
+    if !legacy_dir.is_dir() {
+        println!(
+            " {} There is no '{}' folder to migrate.",
+            SAD,
+            legacy_dir.display()
+        );
+        return Ok(());
+    }
+    if fs::canonicalize(&legacy_dir)? == fs::canonicalize(&config_dir).unwrap_or_default() {
+        println!(
+            " {} '{}' is already the global configuration folder.",
+            SAD,
+            legacy_dir.display()
+        );
+        return Ok(());
+    }
+    if fs::read_dir(&config_dir).is_ok_and(|mut entries| entries.next().is_some()) {
+        return Err(anyhow!(
+            "'{}' already exists, move the files of '{}' into it by hand",
+            config_dir.display(),
+            legacy_dir.display()
+        ));
+    }
+
+    println!(
+        " {} Migrate '{}' to '{}'...",
+        WRITE,
+        legacy_dir.display(),
+        config_dir.display()
+    );
+    let state_dir = config_dirs::global_state_dir(&config_dir);
+    let lock = match options.dry_run {
+        true => None,
+        false => Some(file_store::lock_folder(&legacy_dir)?),
+    };
+
+    // The history goes to its own folder, the lock is not moved
+    let mut paths: Vec<PathBuf> = fs::read_dir(&legacy_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .collect();
+    paths.sort();
+    let mut moves: Vec<(PathBuf, PathBuf)> = vec![];
+    for path in paths {
+        let name = match path.file_name() {
+            Some(name) => name.to_os_string(),
+            None => continue,
+        };
+        let target_dir = match name.to_str() {
+            Some(".lock") => continue,
+            Some("journal") => &state_dir,
+            _ => &config_dir,
+        };
+        moves.push((path, target_dir.join(name)));
+    }
+
+    for (from, to) in &moves {
+        match options.dry_run {
+            true => println!(
+                " {} Move '{}' to '{}'",
+                DRY_RUN,
+                from.display(),
+                to.display()
+            ),
+            false => {
+                if let Some(folder) = to.parent() {
+                    fs::create_dir_all(folder)?;
+                }
+                fs::rename(from, to).map_err(|err| {
+                    anyhow!(
+                        "unable to move '{}' to '{}': {}",
+                        from.display(),
+                        to.display(),
+                        err
+                    )
+                })?;
+            }
+        }
+    }
+    if options.dry_run {
+        return Ok(());
+    }
+
+    // The entries of the global history now target the moved files
+    WintsJournal::new(state_dir.join("journal")).relocate(&legacy_dir, &config_dir)?;
+    drop(lock);
+    let _ = fs::remove_file(legacy_dir.join(".lock"));
+    fs::remove_dir(&legacy_dir)?;
+    println!(
+        " {} {} files have been moved from '{}'",
+        WRITE,
+        moves.len(),
+        legacy_dir.display()
+    );
+
+    Ok(())
+}
@@ -0,0 +1,117 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::core::storage::WintsStorage;
+use crate::log_line;
+use crate::ops::wints_scan::{
+    canonicalise_scanned_urls, find_stale_urls, list_scanned_files, url_scanner_options,
+    ScanSelectionOptions,
+};
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::file_store;
+use crate::util::log;
+use crate::util::log::{DONE, DRY_RUN, INFO, SAD, SEARCH, WRITE};
+use crate::util::url_scanner;
+
+const OBSOLETE_CONTEXT: &str = "obsolete";
+
+pub struct PruneOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    pub module_name: String,
+    pub global_module: bool,
+    pub scan_path: PathBuf,
+    // Same file selection as the scan which found the URLs
+    pub selection: ScanSelectionOptions,
+    pub obsolete: bool,
+    pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
+}
+
+pub fn prune(options: PruneOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
+    let scan_path = file_store::resolve_path(&options.scan_path);
+    log_line!(" {} Using module '{}'", INFO, options.module_name);
+    if options.dry_run {
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
+    }
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+
+    log_line!(" {} Scanning for stale URLs...", SEARCH);
+    let url_scanner_options = url_scanner_options(
+        &storage,
+        &options.module_name,
+        std::slice::from_ref(&scan_path),
+        &options.selection,
+    )?;
+    let scanned_files = list_scanned_files(&url_scanner_options);
+    let normaliser = storage.url_normaliser();
+    let scanned_urls = canonicalise_scanned_urls(
+        &normaliser,
+        url_scanner_options
+            .into_iter()
+            .flat_map(url_scanner::scan_urls_with_context)
+            .collect(),
+    );
+    let stale_urls = match storage.find_module(options.module_name.clone()) {
+        None => vec![],
+        Some(module) => find_stale_urls(
+            module,
+            &normaliser,
+            &storage.project_root(),
+            &scan_path,
+            &scanned_files,
+            &scanned_urls,
+        ),
+    };
+    if stale_urls.is_empty() {
+        log_line!(" {} Prune found no stale urls.", SAD);
+        return Ok(());
+    }
+
+    for stale_url in &stale_urls {
+        match (options.dry_run, options.obsolete) {
+            (true, true) => log_line!(
+                " {} Move '{}' to the context '{}'",
+                DRY_RUN,
+                stale_url,
+                OBSOLETE_CONTEXT
+            ),
+            (true, false) => log_line!(" {} Remove '{}'", DRY_RUN, stale_url),
+            (false, true) => log_line!(
+                " {} '{}' have been moved to the context '{}'",
+                WRITE,
+                stale_url,
+                OBSOLETE_CONTEXT
+            ),
+            (false, false) => log_line!(" {} '{}' have been removed", WRITE, stale_url),
+        }
+    }
+
+    storage.remove_urls(
+        options.module_name.clone(),
+        options.global_module,
+        &stale_urls,
+    );
+    if options.obsolete {
+        storage.contextualise_urls(
+            options.module_name.clone(),
+            options.global_module,
+            OBSOLETE_CONTEXT,
+            stale_urls,
+        );
+    }
+    let changed_files = storage.store()?;
+    if options.dry_run {
+        file_changes::report_changes(&changed_files, options.dry_run_format)?;
+    }
+    log_line!(" {} Prune completed.", DONE);
+
+    Ok(())
+}
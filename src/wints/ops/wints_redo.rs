@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::core::storage::WintsStorage;
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::log;
+use crate::util::log::{DRY_RUN, SAD, WRITE};
+
+pub struct RedoOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    // Use the journal of the commands changing the global configuration only
+    pub global: bool,
+    pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
+}
+
+pub fn redo(options: RedoOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
+    if options.dry_run {
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
+    }
+
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+    let journal = match options.global {
+        true => storage.global_journal(),
+        false => storage.journal(),
+    };
+    let entry = match journal.first_undone()? {
+        Some(entry) => entry,
+        None => {
+            log_line!(" {} Nothing to redo.", SAD);
+            return Ok(());
+        }
+    };
+
+    log_line!(" {} Redo #{} '{}'...", WRITE, entry.id, entry.command);
+    // The configuration folders are locked while the files are restored
+    let _locks = match options.dry_run {
+        true => vec![],
+        false => storage.lock_folders_of(&entry.files)?,
+    };
+    let changed_files = journal.redo(&entry, options.dry_run)?;
+    match options.dry_run {
+        true => file_changes::report_changes(&changed_files, options.dry_run_format)?,
+        false => log_line!(
+            " {} {} files have been restored as after #{}",
+            WRITE,
+            changed_files.len(),
+            entry.id
+        ),
+    }
+
+    Ok(())
+}
@@ -1,95 +1,592 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 
+use crate::core::module::WintsModule;
 use crate::core::storage::WintsStorage;
-use crate::util::log::{DONE, DRY_RUN, INFO, SAD, SEARCH, WRITE};
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::file_store;
+use crate::util::git_history;
+use crate::util::git_history::{GitHistoryOptions, GIT_SOURCE_PREFIX};
+use crate::util::html_links::parse_site_root;
+use crate::util::log;
+use crate::util::log::{CAUTION, DONE, DRY_RUN, INFO, LOCATION, SAD, SEARCH, WRITE};
+use crate::util::manifests;
+use crate::util::noise_filter::NoiseFilter;
+use crate::util::scan_cache::{ScanCache, ScanCacheStats};
+use crate::util::scan_selection::ScanSelection;
+use crate::util::url_normaliser::UrlNormaliser;
 use crate::util::url_scanner;
+use crate::util::url_scanner::{ScannedUrl, UrlScannerOptions};
+
+const DEFAULT_CONTEXT: &str = "need to be contextualised";
 
 pub struct ScanOptions {
     pub local_basedir: PathBuf,
     pub global_basedir: PathBuf,
     pub module_name: String,
     pub global_module: bool,
-    pub scan_path: PathBuf,
+    pub scan_paths: Vec<PathBuf>,
+    pub selection: ScanSelectionOptions,
+    // Also scan the commit messages of the repositories holding the scanned paths
+    pub git_log: bool,
+    pub git_since: Option<String>,
+    pub git_diffs: bool,
+    // Add the project, documentation and dependency URLs declared by the manifests
+    pub manifests: bool,
+    pub infer_context: bool,
+    pub report: bool,
+    pub cache: Option<bool>,
+    pub stale: bool,
+    // Replace the noise filters of the options when set
+    pub noise_filters: Option<Vec<NoiseFilter>>,
+    pub disabled_noise_filters: Vec<NoiseFilter>,
     pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
+}
+
+// File selection shared by scan and prune, the options.yaml values are used when not set
+#[derive(Clone, Default)]
+pub struct ScanSelectionOptions {
+    pub types: Vec<String>,
+    pub types_not: Vec<String>,
+    pub max_depth: Option<usize>,
+    pub max_filesize: Option<u64>,
+    pub hidden: Option<bool>,
+    pub follow_symlinks: Option<bool>,
+    pub gitignore: Option<bool>,
+    // Extensions of the source files where only comments and docstrings are scanned
+    pub comments_only: Option<Vec<String>>,
+    // URL of the scanned paths, to resolve the relative links of HTML files
+    pub site_root: Option<String>,
 }
 
 pub fn scan(options: ScanOptions) -> Result<()> {
-    println!(" {} Using module '{}'", INFO, options.module_name);
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
+    // Resolved scan paths give sources relative to the project root, wherever wints runs
+    let options = ScanOptions {
+        scan_paths: options
+            .scan_paths
+            .iter()
+            .map(|scan_path| file_store::resolve_path(scan_path))
+            .collect(),
+        ..options
+    };
+    log_line!(" {} Using module '{}'", INFO, options.module_name);
     if options.dry_run {
-        println!(" {} Dry-run mode activated.", DRY_RUN);
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
     }
-    let mut storage = WintsStorage::load(
+    let mut storage = WintsStorage::load_for_update(
         options.local_basedir.clone(),
         options.global_basedir.clone(),
+        options.dry_run,
     )?;
 
-    println!(" {} Scanning for new URLs...", SEARCH);
-    let url_scanner_options = url_scanner::UrlScannerOptions {
-        ignore_files: storage.list_of_ignore_files(),
-        scan_path: options.scan_path.clone(),
+    log_line!(" {} Scanning for new URLs...", SEARCH);
+    let url_scanner_options = url_scanner_options(
+        &storage,
+        &options.module_name,
+        &options.scan_paths,
+        &options.selection,
+    )?;
+    let manifest_urls = match options.manifests {
+        true => find_new_manifest_urls(&options, &storage, &url_scanner_options),
+        false => vec![],
     };
-    let scanned_urls = url_scanner::scan_urls(url_scanner_options);
+    let mut scanned_urls = scan_urls(&options, &storage, url_scanner_options.clone())?;
+    if options.git_log {
+        scanned_urls.extend(scan_git_history(&options)?);
+    }
+    let scanned_urls = canonicalise_scanned_urls(&storage.url_normaliser(), scanned_urls);
+    if options.report {
+        report_scanned_urls(&storage.project_root(), &scanned_urls);
+    }
+    if options.stale {
+        report_stale_urls(&options, &storage, &url_scanner_options, &scanned_urls);
+    }
+    let scanned_urls = filter_noise(&options, &storage, scanned_urls);
     let new_urls = find_new_urls(&options, &storage, scanned_urls);
-    match new_urls.len() {
-        0 => println!(" {} Scan found no new urls.", SAD),
+    let mut contextualised_urls = contextualise_new_urls(&options, &new_urls);
+    // The context declared by a manifest wins over the one given by the scan
+    for (_, urls) in contextualised_urls.iter_mut() {
+        urls.retain(|url| {
+            !manifest_urls
+                .iter()
+                .any(|(_, manifest_urls)| manifest_urls.contains(url))
+        });
+    }
+    contextualised_urls.retain(|(_, urls)| !urls.is_empty());
+    contextualised_urls.extend(manifest_urls);
+    match contextualised_urls.len() {
+        0 => log_line!(" {} Scan found no new urls.", SAD),
         _ => {
-            append_new_urls(options, &mut storage, new_urls)?;
-            println!(" {} Scan completed.", DONE);
+            append_new_urls(options, &mut storage, contextualised_urls, new_urls)?;
+            log_line!(" {} Scan completed.", DONE);
         }
     }
     Ok(())
 }
 
+// Walk of each scanned path, with the same file selection for scan and prune
+pub fn url_scanner_options(
+    storage: &WintsStorage,
+    module_name: &str,
+    scan_paths: &[PathBuf],
+    selection_options: &ScanSelectionOptions,
+) -> Result<Vec<UrlScannerOptions>> {
+    let selection = scan_selection(selection_options, storage)?;
+    let comments_only = selection_options
+        .comments_only
+        .clone()
+        .or(storage.scan_config().comments_only)
+        .unwrap_or_default();
+    let site_root = match &selection_options.site_root {
+        Some(site_root) => Some(parse_site_root(site_root)?),
+        None => storage.scan_config().site_root()?,
+    };
+    Ok(scan_paths
+        .iter()
+        .map(|scan_path| UrlScannerOptions {
+            ignore_files: storage.list_of_ignore_files(module_name.to_string()),
+            ignore_globs: storage.list_of_module_ignore_globs(module_name.to_string()),
+            scan_path: scan_path.clone(),
+            ignore_root: storage.project_root(),
+            threads: 0,
+            selection: selection.clone(),
+            comments_only: comments_only.clone(),
+            site_root: site_root.clone(),
+        })
+        .collect())
+}
+
+// Command line values take precedence over the options.yaml ones
+fn scan_selection(options: &ScanSelectionOptions, storage: &WintsStorage) -> Result<ScanSelection> {
+    let config_selection = storage.scan_config().selection()?;
+    let selection = ScanSelection {
+        types: match options.types.is_empty() {
+            true => config_selection.types,
+            false => options.types.clone(),
+        },
+        types_not: match options.types_not.is_empty() {
+            true => config_selection.types_not,
+            false => options.types_not.clone(),
+        },
+        max_depth: options.max_depth.or(config_selection.max_depth),
+        max_filesize: options.max_filesize.or(config_selection.max_filesize),
+        hidden: options.hidden.unwrap_or(config_selection.hidden),
+        follow_symlinks: options
+            .follow_symlinks
+            .unwrap_or(config_selection.follow_symlinks),
+        gitignore: options.gitignore.unwrap_or(config_selection.gitignore),
+    };
+    selection.validate()?;
+    Ok(selection)
+}
+
+fn scan_urls(
+    options: &ScanOptions,
+    storage: &WintsStorage,
+    url_scanner_options: Vec<UrlScannerOptions>,
+) -> Result<Vec<ScannedUrl>> {
+    let use_cache = options
+        .cache
+        .or(storage.scan_config().cache)
+        .unwrap_or(false);
+    if !use_cache {
+        return Ok(url_scanner_options
+            .into_iter()
+            .flat_map(url_scanner::scan_urls_with_context)
+            .collect());
+    }
+
+    let cache_path = storage.scan_cache_path();
+    let mut cache = ScanCache::load(&cache_path);
+    let mut scanned_urls = vec![];
+    let mut stats = ScanCacheStats::default();
+    for url_scanner_options in url_scanner_options {
+        let (path_scanned_urls, path_stats) =
+            url_scanner::scan_urls_with_cache(url_scanner_options, &mut cache);
+        scanned_urls.extend(path_scanned_urls);
+        stats.reused_files += path_stats.reused_files;
+        stats.scanned_files += path_stats.scanned_files;
+    }
+    log_line!(
+        " {} {} unchanged files reused from the cache, {} files scanned.",
+        INFO,
+        stats.reused_files,
+        stats.scanned_files
+    );
+    if !options.dry_run {
+        cache.store(&cache_path)?;
+    }
+    Ok(scanned_urls)
+}
+
+// Contexts of the URLs declared by the manifests, the known and ignored URLs are left out
+fn find_new_manifest_urls(
+    options: &ScanOptions,
+    storage: &WintsStorage,
+    url_scanner_options: &[UrlScannerOptions],
+) -> Vec<(String, Vec<String>)> {
+    let normaliser = storage.url_normaliser();
+    let known_urls: HashSet<String> = storage
+        .list_of_all_urls(options.module_name.clone())
+        .into_iter()
+        .map(|url| normaliser.canonicalise(&url))
+        .collect();
+    let ignore_list = storage.url_ignore_list(options.module_name.clone());
+
+    let mut manifest_paths: Vec<PathBuf> = vec![];
+    for url_scanner_options in url_scanner_options {
+        // Manifests are read whatever the selected file types
+        let url_scanner_options = UrlScannerOptions {
+            selection: ScanSelection {
+                types: vec![],
+                types_not: vec![],
+                ..url_scanner_options.selection.clone()
+            },
+            ..url_scanner_options.clone()
+        };
+        for path in url_scanner::list_files(url_scanner_options) {
+            if manifests::is_manifest(&path) && !manifest_paths.contains(&path) {
+                manifest_paths.push(path);
+            }
+        }
+    }
+
+    let mut contextualised_urls: Vec<(String, Vec<String>)> = vec![];
+    for manifest_path in manifest_paths {
+        let manifest = match manifests::read_manifest(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                log_line!(" {} {}", CAUTION, err);
+                continue;
+            }
+        };
+        log_line!(
+            " {} Reading the manifest '{}'...",
+            SEARCH,
+            manifest_path.display()
+        );
+        for (context, url) in manifest.contexts() {
+            let url = normaliser.canonicalise(&url);
+            if known_urls.contains(&url)
+                || ignore_list.is_ignored(&url)
+                || contextualised_urls
+                    .iter()
+                    .any(|(_, urls)| urls.contains(&url))
+            {
+                continue;
+            }
+            match contextualised_urls
+                .iter_mut()
+                .find(|(existing_context, _)| *existing_context == context)
+            {
+                Some((_, urls)) => urls.push(url),
+                None => contextualised_urls.push((context, vec![url])),
+            }
+        }
+    }
+    contextualised_urls
+}
+
+fn scan_git_history(options: &ScanOptions) -> Result<Vec<ScannedUrl>> {
+    let mut repositories: Vec<PathBuf> = vec![];
+    for scan_path in &options.scan_paths {
+        let repository = git_history::repository_root(scan_path)?;
+        if !repositories.contains(&repository) {
+            repositories.push(repository);
+        }
+    }
+    let mut scanned_urls = vec![];
+    for repository in repositories {
+        log_line!(
+            " {} Scanning the git history of '{}'...",
+            SEARCH,
+            repository.display()
+        );
+        scanned_urls.extend(git_history::scan_git_history(&GitHistoryOptions {
+            repository,
+            since: options.git_since.clone(),
+            diffs: options.git_diffs,
+        })?);
+    }
+    Ok(scanned_urls)
+}
+
+pub fn canonicalise_scanned_urls(
+    normaliser: &UrlNormaliser,
+    scanned_urls: Vec<ScannedUrl>,
+) -> Vec<ScannedUrl> {
+    let mut canonical_urls: Vec<ScannedUrl> = scanned_urls
+        .into_iter()
+        .map(|scanned_url| ScannedUrl {
+            url: normaliser.canonicalise(&scanned_url.url),
+            ..scanned_url
+        })
+        .collect();
+    canonical_urls.sort();
+    canonical_urls.dedup();
+    canonical_urls
+}
+
+// Drop the noisy URLs, and report how many distinct URLs each filter dropped
+fn filter_noise(
+    options: &ScanOptions,
+    storage: &WintsStorage,
+    scanned_urls: Vec<ScannedUrl>,
+) -> Vec<ScannedUrl> {
+    let noise_filters: Vec<NoiseFilter> = options
+        .noise_filters
+        .clone()
+        .unwrap_or_else(|| storage.scan_config().enabled_noise_filters())
+        .into_iter()
+        .filter(|filter| !options.disabled_noise_filters.contains(filter))
+        .collect();
+    if noise_filters.is_empty() {
+        return scanned_urls;
+    }
+
+    let mut dropped_urls: Vec<(NoiseFilter, HashSet<String>)> = vec![];
+    let kept_urls = scanned_urls
+        .into_iter()
+        .filter(
+            |scanned_url| match NoiseFilter::find_matching(&noise_filters, &scanned_url.url) {
+                None => true,
+                Some(filter) => {
+                    match dropped_urls
+                        .iter_mut()
+                        .find(|(dropped, _)| *dropped == filter)
+                    {
+                        Some((_, urls)) => {
+                            urls.insert(scanned_url.url.clone());
+                        }
+                        None => {
+                            dropped_urls.push((filter, HashSet::from([scanned_url.url.clone()])))
+                        }
+                    }
+                    false
+                }
+            },
+        )
+        .collect();
+    dropped_urls.sort_by_key(|(filter, _)| *filter);
+    for (filter, urls) in dropped_urls {
+        log_line!(
+            " {} Noise filter '{}' dropped {} {}.",
+            INFO,
+            filter,
+            urls.len(),
+            match urls.len() {
+                1 => "URL",
+                _ => "URLs",
+            }
+        );
+    }
+    kept_urls
+}
+
 fn append_new_urls(
     options: ScanOptions,
     storage: &mut WintsStorage,
-    new_urls: Vec<String>,
+    contextualised_urls: Vec<(String, Vec<String>)>,
+    new_urls: Vec<ScannedUrl>,
 ) -> Result<()> {
-    let url_word = match new_urls.len() {
-        1 => "URL",
-        _ => "URLs",
-    };
-    let context = "need to be contextualised";
-    match options.dry_run {
-        true => println!(
-            " {} Append {} {} on the context '{}'.",
-            DRY_RUN,
-            new_urls.len(),
-            url_word,
-            context
-        ),
-        false => {
-            storage.contextualise_urls(
-                options.module_name,
-                options.global_module,
-                context,
-                new_urls.clone(),
-            );
-            storage.store()?;
-            println!(
+    for (context, urls) in contextualised_urls {
+        let url_word = match urls.len() {
+            1 => "URL",
+            _ => "URLs",
+        };
+        match options.dry_run {
+            true => log_line!(
+                " {} Append {} {} on the context '{}'.",
+                DRY_RUN,
+                urls.len(),
+                url_word,
+                context
+            ),
+            false => log_line!(
                 " {} {} {} have been added in context '{}', ready to be sorted.",
                 WRITE,
-                new_urls.len(),
+                urls.len(),
                 url_word,
                 context
-            );
+            ),
         }
+        storage.contextualise_urls(
+            options.module_name.clone(),
+            options.global_module,
+            context.as_str(),
+            urls,
+        );
+    }
+    let project_root = storage.project_root();
+    for new_url in new_urls {
+        storage.record_sources(
+            options.module_name.clone(),
+            options.global_module,
+            new_url.url.clone(),
+            vec![new_url.source(&project_root)],
+        );
+    }
+    let changed_files = storage.store()?;
+    if options.dry_run {
+        file_changes::report_changes(&changed_files, options.dry_run_format)?;
     }
     Ok(())
 }
 
-fn find_new_urls(
+// Group the new URLs by context, the first suggestion of an URL wins
+fn contextualise_new_urls(
+    options: &ScanOptions,
+    new_urls: &[ScannedUrl],
+) -> Vec<(String, Vec<String>)> {
+    let mut contextualised_urls: Vec<(String, Vec<String>)> = vec![];
+    for new_url in new_urls {
+        if contextualised_urls
+            .iter()
+            .any(|(_, urls)| urls.contains(&new_url.url))
+        {
+            continue;
+        }
+        let context = match options.infer_context {
+            true => new_url
+                .suggested_context()
+                .unwrap_or_else(|| DEFAULT_CONTEXT.to_string()),
+            false => DEFAULT_CONTEXT.to_string(),
+        };
+        match contextualised_urls
+            .iter_mut()
+            .find(|(existing_context, _)| *existing_context == context)
+        {
+            Some((_, urls)) => urls.push(new_url.url.clone()),
+            None => contextualised_urls.push((context, vec![new_url.url.clone()])),
+        }
+    }
+    contextualised_urls
+}
+
+fn report_scanned_urls(project_root: &Path, scanned_urls: &[ScannedUrl]) {
+    let mut previous_url: Option<&str> = None;
+    for scanned_url in scanned_urls {
+        if previous_url != Some(scanned_url.url.as_str()) {
+            log_line!(" {} {}", LOCATION, scanned_url.url);
+            previous_url = Some(scanned_url.url.as_str());
+        }
+        log_line!("    {}", scanned_url.source(project_root));
+    }
+}
+
+fn report_stale_urls(
     options: &ScanOptions,
     storage: &WintsStorage,
-    scanned_urls: Vec<String>,
+    url_scanner_options: &[UrlScannerOptions],
+    scanned_urls: &[ScannedUrl],
+) {
+    let mut stale_urls: Vec<String> = vec![];
+    let project_root = storage.project_root();
+    let scanned_files = list_scanned_files(url_scanner_options);
+    if let Some(module) = storage.find_module(options.module_name.clone()) {
+        for scan_path in &options.scan_paths {
+            for stale_url in find_stale_urls(
+                module,
+                &storage.url_normaliser(),
+                &project_root,
+                scan_path,
+                &scanned_files,
+                scanned_urls,
+            ) {
+                if stale_urls.contains(&stale_url) {
+                    continue;
+                }
+                log_line!(
+                    " {} '{}' is no longer found under '{}'",
+                    CAUTION,
+                    stale_url,
+                    scan_path.display()
+                );
+                stale_urls.push(stale_url);
+            }
+        }
+    }
+    match stale_urls.len() {
+        0 => log_line!(" {} Scan found no stale urls.", INFO),
+        count => log_line!(
+            " {} {} stale {}, use 'wints prune' to remove them.",
+            INFO,
+            count,
+            match count {
+                1 => "URL",
+                _ => "URLs",
+            }
+        ),
+    }
+}
+
+pub fn list_scanned_files(url_scanner_options: &[UrlScannerOptions]) -> HashSet<PathBuf> {
+    url_scanner_options
+        .iter()
+        .cloned()
+        .flat_map(url_scanner::list_files)
+        .collect()
+}
+
+// URLs found by a previous scan under this path, but not anymore, the sources are relative to
+// the project root, and the existing files left out of the scanned files cannot tell
+pub fn find_stale_urls(
+    module: &WintsModule,
+    normaliser: &UrlNormaliser,
+    project_root: &Path,
+    scan_path: &Path,
+    scanned_files: &HashSet<PathBuf>,
+    scanned_urls: &[ScannedUrl],
 ) -> Vec<String> {
-    let known_urls = storage.list_of_all_urls(options.module_name.clone());
-    let ignored_urls = storage.ignored_urls();
+    let scan_root = file_store::resolve_path(scan_path);
+    let known_urls = module.list_of_all_urls();
+    let found_urls: HashSet<String> = scanned_urls
+        .iter()
+        .map(|scanned_url| normaliser.canonicalise(&scanned_url.url))
+        .collect();
+
+    module
+        .list_of_urls_with_sources()
+        .into_iter()
+        .filter(|url| known_urls.contains(url))
+        .filter(|url| {
+            module.list_of_sources(url).iter().any(|source| {
+                if source.starts_with(GIT_SOURCE_PREFIX) {
+                    return false;
+                }
+                let source_path = project_root.join(ScannedUrl::path_of_source(source));
+                source_path.starts_with(&scan_root)
+                    && (scanned_files.contains(&source_path) || !source_path.exists())
+            })
+        })
+        .filter(|url| !found_urls.contains(&normaliser.canonicalise(url)))
+        .collect()
+}
+
+fn find_new_urls(
+    options: &ScanOptions,
+    storage: &WintsStorage,
+    scanned_urls: Vec<ScannedUrl>,
+) -> Vec<ScannedUrl> {
+    let normaliser = storage.url_normaliser();
+    let known_urls: HashSet<String> = storage
+        .list_of_all_urls(options.module_name.clone())
+        .into_iter()
+        .map(|url| normaliser.canonicalise(&url))
+        .collect();
+    let ignore_list = storage.url_ignore_list(options.module_name.clone());
 
     scanned_urls
         .into_iter()
-        .filter(|url| !known_urls.contains(url) && !ignored_urls.contains(url))
+        .filter(|scanned_url| {
+            !known_urls.contains(&normaliser.canonicalise(&scanned_url.url))
+                && !ignore_list.is_ignored(&scanned_url.url)
+        })
         .collect()
 }
@@ -6,6 +6,7 @@ use anyhow::Result;
 use crate::core::module::WintsModule;
 use crate::core::storage::WintsStorage;
 use crate::util::log::{CAUTION, DONE, DRY_RUN, GOTO, INFO, SAD, SEARCH, TRY};
+use crate::util::url_normaliser::UrlNormaliser;
 
 pub struct SearchOptions {
     pub local_basedir: PathBuf,
@@ -32,7 +33,7 @@ pub fn search(options: SearchOptions) -> Result<()> {
     };
 
     match options.matching_terms {
-        Some(_) => search_urls_based_on_terms(&options, module),
+        Some(_) => search_urls_based_on_terms(&options, &storage.url_normaliser(), module),
         None => terms_are_mandatory(module),
     }
     Ok(())
@@ -60,7 +61,11 @@ fn terms_are_mandatory(module: WintsModule) {
     }
 }
 
-fn search_urls_based_on_terms(options: &SearchOptions, module: WintsModule) {
+fn search_urls_based_on_terms(
+    options: &SearchOptions,
+    normaliser: &UrlNormaliser,
+    module: WintsModule,
+) {
     let matching_terms = options.matching_terms.clone().unwrap();
 
     println!(
@@ -69,7 +74,10 @@ fn search_urls_based_on_terms(options: &SearchOptions, module: WintsModule) {
         matching_terms.join(" ")
     );
 
-    let urls = module.list_of_urls_from_matching_context(matching_terms);
+    let urls = dedup_urls(
+        normaliser,
+        module.list_of_urls_from_matching_context(matching_terms),
+    );
     match urls.is_empty() {
         true => urls_not_found(options, module),
         false => open_urls(options, urls),
@@ -78,6 +86,23 @@ fn search_urls_based_on_terms(options: &SearchOptions, module: WintsModule) {
     println!(" {} Search completed.", DONE)
 }
 
+// The same URL can be in several matching contexts, open it only once
+fn dedup_urls(normaliser: &UrlNormaliser, urls: Vec<String>) -> Vec<String> {
+    let mut canonical_urls: Vec<String> = vec![];
+    urls.into_iter()
+        .filter(|url| {
+            let canonical_url = normaliser.canonicalise(url);
+            match canonical_urls.contains(&canonical_url) {
+                true => false,
+                false => {
+                    canonical_urls.push(canonical_url);
+                    true
+                }
+            }
+        })
+        .collect()
+}
+
 fn open_urls(options: &SearchOptions, urls: Vec<String>) {
     for url in urls.iter() {
         match options.dry_run {
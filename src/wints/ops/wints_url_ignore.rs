@@ -2,45 +2,122 @@ use std::path::PathBuf;
 
 use anyhow::Result;
 
+use crate::core::ignore_pattern::{IgnorePattern, IgnoreRule};
 use crate::core::storage::WintsStorage;
-use crate::util::log::{DRY_RUN, WRITE};
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::log;
+use crate::util::log::{DRY_RUN, INFO, WRITE};
 
 pub struct IgnoreUrlOptions {
     pub local_basedir: PathBuf,
     pub global_basedir: PathBuf,
     pub global: bool,
     pub url: String,
+    pub pattern: bool,
+    pub prefix: bool,
+    pub regex: bool,
+    pub reason: Option<String>,
+    pub expires: Option<String>,
     pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
 }
 
 pub fn ignore_url(options: IgnoreUrlOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
     if options.dry_run {
-        println!(" {} Dry-run mode activated.", DRY_RUN);
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
     }
 
     let scope = match options.global {
         true => "global ignore list",
         false => "ignore list",
     };
-    println!(" {} Add '{}' to the {}...", WRITE, options.url, scope);
+    log_line!(" {} Add '{}' to the {}...", WRITE, options.url, scope);
 
+    let rule = ignore_rule(&options);
+    let pattern = match (&rule, &options.reason, &options.expires) {
+        (None, None, None) => None,
+        _ => Some(IgnorePattern::new(
+            rule.unwrap_or_else(|| IgnoreRule::Url(options.url.clone())),
+            options.reason.clone(),
+            options.expires.clone(),
+        )?),
+    };
+
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+    match pattern {
+        Some(pattern) => ignore_with_pattern(&mut storage, &options, pattern, scope),
+        None => ignore_exact_url(&mut storage, &options, scope),
+    }
+}
+
+fn ignore_rule(options: &IgnoreUrlOptions) -> Option<IgnoreRule> {
+    let value = options.url.clone();
+    match (options.pattern, options.prefix, options.regex) {
+        (true, _, _) if !value.contains("://") => Some(IgnoreRule::Host(value)),
+        (true, _, _) => Some(IgnoreRule::Glob(value)),
+        (_, true, _) => Some(IgnoreRule::Prefix(value)),
+        (_, _, true) => Some(IgnoreRule::Regex(value)),
+        _ => None,
+    }
+}
+
+fn ignore_exact_url(
+    storage: &mut WintsStorage,
+    options: &IgnoreUrlOptions,
+    scope: &str,
+) -> Result<()> {
+    let normaliser = storage.url_normaliser();
+    let url = normaliser.canonicalise(&options.url);
+    if storage
+        .ignored_urls()
+        .iter()
+        .any(|ignored_url| normaliser.same_url(ignored_url, &url))
+    {
+        log_line!(" {} '{}' is already ignored", INFO, url);
+        return Ok(());
+    }
+    storage.ignore_url(url.clone(), options.global);
+    store(storage, options, &url, scope)
+}
+
+fn ignore_with_pattern(
+    storage: &mut WintsStorage,
+    options: &IgnoreUrlOptions,
+    pattern: IgnorePattern,
+    scope: &str,
+) -> Result<()> {
+    if storage
+        .ignored_patterns()
+        .iter()
+        .any(|ignored_pattern| ignored_pattern.rule == pattern.rule)
+    {
+        log_line!(" {} {} is already ignored", INFO, pattern.rule);
+        return Ok(());
+    }
+    storage.ignore_pattern(pattern, options.global);
+    store(storage, options, &options.url, scope)
+}
+
+fn store(
+    storage: &mut WintsStorage,
+    options: &IgnoreUrlOptions,
+    url: &str,
+    scope: &str,
+) -> Result<()> {
+    let changed_files = storage.store()?;
     match options.dry_run {
         true => {
-            println!(" {} Add '{}' to the {}", DRY_RUN, options.url, scope);
-        }
-        false => {
-            let mut storage = WintsStorage::load(
-                options.local_basedir.clone(),
-                options.global_basedir.clone(),
-            )?;
-            storage.ignore_url(options.url.clone(), options.global);
-            storage.store()?;
-            println!(
-                " {} '{}' have been added to the {}",
-                WRITE, options.url, scope
-            );
+            log_line!(" {} Add '{}' to the {}", DRY_RUN, url, scope);
+            file_changes::report_changes(&changed_files, options.dry_run_format)?;
         }
+        false => log_line!(" {} '{}' have been added to the {}", WRITE, url, scope),
     }
-
     Ok(())
 }
@@ -3,6 +3,10 @@ use std::path::PathBuf;
 use anyhow::Result;
 
 use crate::core::storage::WintsStorage;
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::log;
 use crate::util::log::{DRY_RUN, WRITE};
 
 pub struct IgnoreGlobOptions {
@@ -11,34 +15,39 @@ pub struct IgnoreGlobOptions {
     pub global: bool,
     pub glob: String,
     pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
 }
 
 pub fn ignore_glob(options: IgnoreGlobOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
     if options.dry_run {
-        println!(" {} Dry-run mode activated.", DRY_RUN);
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
     }
 
     let scope = match options.global {
         true => "global ignore list",
         false => "ignore list",
     };
-    println!(" {} Add '{}' to the {}...", WRITE, options.glob, scope);
+    log_line!(" {} Add '{}' to the {}...", WRITE, options.glob, scope);
 
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+    storage.ignore_glob(options.glob.clone(), options.global);
+    let changed_files = storage.store()?;
     match options.dry_run {
         true => {
-            println!(" {} Add '{}' to the {}", DRY_RUN, options.glob, scope);
-        }
-        false => {
-            let storage = WintsStorage::load(
-                options.local_basedir.clone(),
-                options.global_basedir.clone(),
-            )?;
-            storage.ignore_glob(options.glob.clone(), options.global)?;
-            println!(
-                " {} '{}' have been added to the {}",
-                WRITE, options.glob, scope
-            );
+            log_line!(" {} Add '{}' to the {}", DRY_RUN, options.glob, scope);
+            file_changes::report_changes(&changed_files, options.dry_run_format)?;
         }
+        false => log_line!(
+            " {} '{}' have been added to the {}",
+            WRITE,
+            options.glob,
+            scope
+        ),
     }
 
     Ok(())
@@ -0,0 +1,56 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::core::storage::{IgnoreScope, WintsStorage};
+use crate::util::log::{INFO, SAD};
+
+pub struct IgnoredOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    pub module_name: String,
+}
+
+pub fn ignored(options: IgnoredOptions) -> Result<()> {
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+
+    let entries: Vec<String> = storage
+        .ignore_scopes(Some(options.module_name.clone()))
+        .iter()
+        .flat_map(describe_scope)
+        .collect();
+    match entries.is_empty() {
+        true => println!(" {} No ignored URLs or globs.", SAD),
+        false => {
+            println!(" {} {} ignore entries:", INFO, entries.len());
+            entries.iter().for_each(|entry| println!("    {}", entry));
+        }
+    }
+    Ok(())
+}
+
+fn describe_scope(scope: &IgnoreScope) -> Vec<String> {
+    let scope_name = &scope.name;
+    let urls = scope
+        .ignored_urls
+        .iter()
+        .map(|url| format!("[{}] url '{}'", scope_name, url));
+    let patterns = scope.ignored_patterns.iter().map(|pattern| {
+        let mut entry = format!("[{}] {}", scope_name, pattern.rule);
+        if let Some(reason) = &pattern.reason {
+            entry.push_str(&format!(", {}", reason));
+        }
+        if let Some(expires) = &pattern.expires {
+            entry.push_str(&format!(" (until {})", expires));
+        }
+        entry
+    });
+    let globs = scope
+        .ignore_globs
+        .iter()
+        .map(|ignore_glob| format!("[{}] path '{}'", scope_name, ignore_glob.glob));
+    urls.chain(patterns).chain(globs).collect()
+}
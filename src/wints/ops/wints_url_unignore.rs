@@ -0,0 +1,58 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+
+use crate::core::storage::WintsStorage;
+use crate::log_line;
+use crate::util::file_changes;
+use crate::util::file_changes::DryRunFormat;
+use crate::util::log;
+use crate::util::log::{DRY_RUN, SAD, WRITE};
+
+pub struct UnignoreUrlOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    pub global: bool,
+    pub url: String,
+    pub dry_run: bool,
+    pub dry_run_format: DryRunFormat,
+}
+
+pub fn unignore_url(options: UnignoreUrlOptions) -> Result<()> {
+    log::set_logs_to_stderr(options.dry_run_format == DryRunFormat::Json);
+    if options.dry_run {
+        log_line!(" {} Dry-run mode activated.", DRY_RUN);
+    }
+
+    let scope = match options.global {
+        true => "global ignore list",
+        false => "ignore list",
+    };
+    log_line!(" {} Remove '{}' from the {}...", WRITE, options.url, scope);
+
+    let mut storage = WintsStorage::load_for_update(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+        options.dry_run,
+    )?;
+    match storage.unignore_url(&options.url, options.global) {
+        0 => log_line!(" {} '{}' is not in the {}", SAD, options.url, scope),
+        _ => {
+            let changed_files = storage.store()?;
+            match options.dry_run {
+                true => {
+                    log_line!(" {} Remove '{}' from the {}", DRY_RUN, options.url, scope);
+                    file_changes::report_changes(&changed_files, options.dry_run_format)?;
+                }
+                false => log_line!(
+                    " {} '{}' have been removed from the {}",
+                    WRITE,
+                    options.url,
+                    scope
+                ),
+            }
+        }
+    }
+
+    Ok(())
+}
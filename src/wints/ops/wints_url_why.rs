@@ -0,0 +1,133 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use ignore::gitignore::{GitignoreBuilder, Glob};
+use ignore::Match;
+
+use crate::core::storage::{IgnoreScope, WintsStorage};
+use crate::util::date;
+use crate::util::file_store;
+use crate::util::log::{INFO, LOCATION, SEARCH};
+use crate::util::noise_filter::NoiseFilter;
+
+pub struct WhyOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    pub module_name: String,
+    // An URL, or a path to check against the ignore globs
+    pub target: String,
+}
+
+pub fn why(options: WhyOptions) -> Result<()> {
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+
+    println!(" {} Looking why '{}' is ignored...", SEARCH, options.target);
+    let reasons = match options.target.contains("://") {
+        true => explain_url(&storage, &options.module_name, &options.target),
+        false => explain_path(&storage, &options.module_name, Path::new(&options.target)),
+    };
+    match reasons.is_empty() {
+        true => println!(" {} '{}' is not ignored", INFO, options.target),
+        false => reasons
+            .iter()
+            .for_each(|reason| println!(" {} {}", LOCATION, reason)),
+    }
+    Ok(())
+}
+
+fn explain_url(storage: &WintsStorage, module_name: &str, url: &str) -> Vec<String> {
+    let normaliser = storage.url_normaliser();
+    let today = date::today();
+    let mut reasons = vec![];
+    for scope in storage.ignore_scopes(Some(module_name.to_string())) {
+        let config_path = scope.config_path.display();
+        for ignored_url in &scope.ignored_urls {
+            if normaliser.same_url(ignored_url, url) {
+                reasons.push(format!(
+                    "Ignored by url '{}' in '{}' ({})",
+                    ignored_url, config_path, scope.name
+                ));
+            }
+        }
+        for pattern in &scope.ignored_patterns {
+            if !pattern.matches(&normaliser, url) {
+                continue;
+            }
+            let mut reason = match pattern.is_expired(&today) {
+                true => format!(
+                    "Was ignored by {} in '{}' ({}), expired since {}",
+                    pattern.rule,
+                    config_path,
+                    scope.name,
+                    pattern.expires.clone().unwrap_or_default()
+                ),
+                false => format!(
+                    "Ignored by {} in '{}' ({})",
+                    pattern.rule, config_path, scope.name
+                ),
+            };
+            if let Some(pattern_reason) = &pattern.reason {
+                reason.push_str(&format!(": {}", pattern_reason));
+            }
+            reasons.push(reason);
+        }
+    }
+    let noise_filters = storage.scan_config().enabled_noise_filters();
+    if let Some(filter) = NoiseFilter::find_matching(&noise_filters, &normaliser.canonicalise(url))
+    {
+        reasons.push(format!("Dropped by the noise filter '{}'", filter));
+    }
+    reasons
+}
+
+// The globs are relative to the project root, wherever the path is given from
+fn explain_path(storage: &WintsStorage, module_name: &str, path: &Path) -> Vec<String> {
+    let project_root = storage.project_root();
+    let is_dir = path.is_dir();
+    let path = file_store::resolve_path(path);
+    let path = path.strip_prefix(&project_root).unwrap_or(&path);
+    storage
+        .ignore_scopes(Some(module_name.to_string()))
+        .iter()
+        .filter_map(|scope| explain_path_in_scope(scope, &project_root, path, is_dir))
+        .collect()
+}
+
+// The deepest matching glob wins, like during the scan
+fn explain_path_in_scope(
+    scope: &IgnoreScope,
+    project_root: &Path,
+    path: &Path,
+    is_dir: bool,
+) -> Option<String> {
+    if scope.ignore_globs.is_empty() {
+        return None;
+    }
+    let mut builder = GitignoreBuilder::new(project_root);
+    for ignore_glob in &scope.ignore_globs {
+        let _ = builder.add_line(Some(ignore_glob.path.clone()), &ignore_glob.glob);
+    }
+    let gitignore = builder.build().ok()?;
+    let describe = |verb: &str, glob: &Glob| {
+        format!(
+            "{} by path '{}' in '{}' ({})",
+            verb,
+            glob.original(),
+            glob.from().unwrap_or(Path::new("")).display(),
+            scope.name
+        )
+    };
+    path.ancestors()
+        .filter(|ancestor| !ancestor.as_os_str().is_empty() && ancestor.parent().is_some())
+        .enumerate()
+        .find_map(
+            |(depth, ancestor)| match gitignore.matched(ancestor, depth > 0 || is_dir) {
+                Match::None => None,
+                Match::Ignore(glob) => Some(describe("Ignored", glob)),
+                Match::Whitelist(glob) => Some(describe("Kept", glob)),
+            },
+        )
+}
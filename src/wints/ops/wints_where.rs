@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::util::config_dirs;
+use crate::util::config_dirs::{GlobalConfigDir, LocalConfigDir};
+use crate::util::log::LOCATION;
+
+pub struct WhereOptions {
+    pub local_config_dir: LocalConfigDir,
+    pub global_config_dir: GlobalConfigDir,
+}
+
+pub fn where_(options: WhereOptions) -> Result<()> {
+    println!(
+        " {} Local configuration: {} ({}){}",
+        LOCATION,
+        options.local_config_dir.path.display(),
+        options.local_config_dir.origin,
+        missing(&options.local_config_dir.path)
+    );
+    let global_basedir = &options.global_config_dir.path;
+    println!(
+        " {} Global configuration: {} ({}){}",
+        LOCATION,
+        global_basedir.display(),
+        options.global_config_dir.origin,
+        missing(global_basedir)
+    );
+    let global_state_dir = config_dirs::global_state_dir(global_basedir);
+    println!(
+        " {} Global history: {}{}",
+        LOCATION,
+        global_state_dir.display(),
+        missing(&global_state_dir)
+    );
+    Ok(())
+}
+
+fn missing(path: &Path) -> &'static str {
+    match path.is_dir() {
+        true => "",
+        false => ", not created yet",
+    }
+}
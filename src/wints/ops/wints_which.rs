@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+use std::process;
+
+use anyhow::Result;
+
+use crate::core::storage::WintsStorage;
+use crate::util::log::{CAUTION, INFO, LOCATION, SAD, SEARCH};
+
+pub struct WhichOptions {
+    pub local_basedir: PathBuf,
+    pub global_basedir: PathBuf,
+    pub module_name: String,
+    pub url: String,
+}
+
+pub fn which(options: WhichOptions) -> Result<()> {
+    println!(" {} Using module '{}'", INFO, options.module_name);
+
+    let storage = WintsStorage::load(
+        options.local_basedir.clone(),
+        options.global_basedir.clone(),
+    )?;
+
+    let module = match storage.find_module(options.module_name.clone()) {
+        None => {
+            println!(" {} Unknown module '{}'", CAUTION, options.module_name);
+            process::exit(1);
+        }
+        Some(module) => module,
+    };
+
+    println!(" {} Looking for '{}'...", SEARCH, options.url);
+    // The module may hold the URL under another form, e.g. canonicalised by a scan
+    let normaliser = storage.url_normaliser();
+    let url = module
+        .list_of_all_urls()
+        .into_iter()
+        .find(|url| normaliser.same_url(url, &options.url))
+        .unwrap_or_else(|| options.url.clone());
+    let contexts = module.list_of_contexts_with_url(url.as_str());
+    if contexts.is_empty() {
+        println!(
+            " {} '{}' is not part of '{}'.",
+            SAD, options.url, options.module_name
+        );
+        return Ok(());
+    }
+    for context in contexts {
+        println!(" {} '{}' is in context '{}'", INFO, options.url, context);
+    }
+
+    let sources = module.list_of_sources(url.as_str());
+    match sources.is_empty() {
+        true => println!(" {} No scan source recorded for '{}'", INFO, options.url),
+        false => sources
+            .iter()
+            .for_each(|source| println!(" {} Found in {}", LOCATION, source)),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories_next::BaseDirs;
+
+// Name of the local configuration folder, looked for from the current directory up to the root
+pub const LOCAL_CONFIG_FOLDER: &str = ".wints";
+// Local configuration folder to use instead of the nearest one
+pub const WINTS_DIR: &str = "WINTS_DIR";
+// Directories where the search of the local configuration folder stops, like GIT_CEILING_DIRECTORIES
+pub const WINTS_CEILING_DIRECTORIES: &str = "WINTS_CEILING_DIRECTORIES";
+// Global configuration folder to use instead of the XDG one
+pub const WINTS_GLOBAL_DIR: &str = "WINTS_GLOBAL_DIR";
+// Name of the global folders inside the XDG base directories
+const XDG_FOLDER: &str = "wints";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigOrigin {
+    Option,
+    Environment,
+    Discovered,
+    Default,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LocalConfigDir {
+    pub path: PathBuf,
+    pub origin: ConfigOrigin,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GlobalConfigOrigin {
+    Option,
+    Environment,
+    // HOME/.wints, used until it is migrated to the XDG folders
+    Legacy,
+    Xdg,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlobalConfigDir {
+    pub path: PathBuf,
+    pub origin: GlobalConfigOrigin,
+}
+
+impl fmt::Display for ConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origin = match self {
+            ConfigOrigin::Option => "set by --config".to_string(),
+            ConfigOrigin::Environment => format!("set by {}", WINTS_DIR),
+            ConfigOrigin::Discovered => "nearest one from the current directory".to_string(),
+            ConfigOrigin::Default => {
+                "none found from the current directory, created here when needed".to_string()
+            }
+        };
+        write!(f, "{}", origin)
+    }
+}
+
+impl fmt::Display for GlobalConfigOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let origin = match self {
+            GlobalConfigOrigin::Option => "set by --global-config".to_string(),
+            GlobalConfigOrigin::Environment => format!("set by {}", WINTS_GLOBAL_DIR),
+            GlobalConfigOrigin::Legacy => format!(
+                "legacy folder, move it to '{}' with 'wints migrate'",
+                xdg_config_dir().display()
+            ),
+            GlobalConfigOrigin::Xdg => "XDG configuration folder".to_string(),
+        };
+        write!(f, "{}", origin)
+    }
+}
+
+// The --global-config option wins over WINTS_GLOBAL_DIR, which wins over the XDG configuration folder,
+// HOME/.wints is still used when there is no XDG configuration folder
+pub fn global_config_dir(option: Option<PathBuf>) -> GlobalConfigDir {
+    if let Some(path) = option {
+        return GlobalConfigDir {
+            path,
+            origin: GlobalConfigOrigin::Option,
+        };
+    }
+    if let Some(path) = env::var_os(WINTS_GLOBAL_DIR).filter(|path| !path.is_empty()) {
+        return GlobalConfigDir {
+            path: PathBuf::from(path),
+            origin: GlobalConfigOrigin::Environment,
+        };
+    }
+    let legacy_dir = legacy_global_dir();
+    let xdg_dir = xdg_config_dir();
+    match legacy_dir.is_dir() && !xdg_dir.is_dir() {
+        true => GlobalConfigDir {
+            path: legacy_dir,
+            origin: GlobalConfigOrigin::Legacy,
+        },
+        false => GlobalConfigDir {
+            path: xdg_dir,
+            origin: GlobalConfigOrigin::Xdg,
+        },
+    }
+}
+
+// Global history, in the XDG state folder when the global configuration is the XDG one,
+// in the global configuration folder otherwise
+pub fn global_state_dir(global_config_dir: &Path) -> PathBuf {
+    match global_config_dir == xdg_config_dir() {
+        true => xdg_dir("XDG_STATE_HOME", &[".local", "state"]),
+        false => global_config_dir.to_path_buf(),
+    }
+}
+
+pub fn xdg_config_dir() -> PathBuf {
+    xdg_dir("XDG_CONFIG_HOME", &[".config"])
+}
+
+pub fn legacy_global_dir() -> PathBuf {
+    home_dir().join(LOCAL_CONFIG_FOLDER)
+}
+
+// Relative paths are invalid in the XDG variables, the default folder of the home directory is used instead
+fn xdg_dir(variable: &str, default_folders: &[&str]) -> PathBuf {
+    let base_dir = match env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+    {
+        Some(path) => path,
+        None => default_folders
+            .iter()
+            .fold(home_dir(), |path, folder| path.join(folder)),
+    };
+    base_dir.join(XDG_FOLDER)
+}
+
+fn home_dir() -> PathBuf {
+    BaseDirs::new()
+        .map(|base_dirs| base_dirs.home_dir().to_path_buf())
+        .unwrap_or_default()
+}
+
+// The --config option wins over WINTS_DIR, which wins over the nearest local configuration folder
+pub fn local_config_dir(option: Option<PathBuf>, global_basedir: &Path) -> LocalConfigDir {
+    if let Some(path) = option {
+        return LocalConfigDir {
+            path,
+            origin: ConfigOrigin::Option,
+        };
+    }
+    if let Some(path) = env::var_os(WINTS_DIR).filter(|path| !path.is_empty()) {
+        return LocalConfigDir {
+            path: PathBuf::from(path),
+            origin: ConfigOrigin::Environment,
+        };
+    }
+    let ceilings: Vec<PathBuf> = env::var_os(WINTS_CEILING_DIRECTORIES)
+        .map(|paths| env::split_paths(&paths).collect())
+        .unwrap_or_default();
+    let global_dirs = [global_basedir.to_path_buf(), legacy_global_dir()];
+    let discovered = env::current_dir()
+        .ok()
+        .and_then(|current_dir| find_local_config_dir(&current_dir, &ceilings, &global_dirs));
+    match discovered {
+        Some(path) => LocalConfigDir {
+            path,
+            origin: ConfigOrigin::Discovered,
+        },
+        None => LocalConfigDir {
+            path: PathBuf::from(LOCAL_CONFIG_FOLDER),
+            origin: ConfigOrigin::Default,
+        },
+    }
+}
+
+// Nearest local configuration folder from the start directory, without looking in a ceiling
+// directory or above, the global configuration folders are never used as a local one
+pub fn find_local_config_dir(
+    start: &Path,
+    ceilings: &[PathBuf],
+    global_dirs: &[PathBuf],
+) -> Option<PathBuf> {
+    let ceilings: Vec<PathBuf> = ceilings
+        .iter()
+        .filter(|ceiling| !ceiling.as_os_str().is_empty())
+        .map(|ceiling| fs::canonicalize(ceiling).unwrap_or_else(|_| ceiling.clone()))
+        .collect();
+    let global_dirs: Vec<PathBuf> = global_dirs
+        .iter()
+        .flat_map(|global_dir| fs::canonicalize(global_dir).ok())
+        .collect();
+    let start = fs::canonicalize(start).unwrap_or_else(|_| start.to_path_buf());
+    for dir in start.ancestors() {
+        if dir != start && ceilings.iter().any(|ceiling| ceiling == dir) {
+            return None;
+        }
+        let candidate = dir.join(LOCAL_CONFIG_FOLDER);
+        if candidate.is_dir() {
+            return match fs::canonicalize(&candidate)
+                .map(|candidate| global_dirs.contains(&candidate))
+                .unwrap_or_default()
+            {
+                true => None,
+                false => Some(candidate),
+            };
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::util::config_dirs::*;
+
+    #[test]
+    fn find_the_nearest_local_config_dir() -> Result<()> {
+        let dir = tempdir()?;
+        let root = fs::canonicalize(dir.path())?;
+        let project = root.join("project");
+        let subdir = project.join("src").join("bin");
+        fs::create_dir_all(&subdir)?;
+        fs::create_dir_all(project.join(LOCAL_CONFIG_FOLDER))?;
+        fs::create_dir_all(root.join("home").join(LOCAL_CONFIG_FOLDER))?;
+        let global_dirs = vec![root.join("home").join(LOCAL_CONFIG_FOLDER)];
+
+        assert_eq!(
+            Some(project.join(LOCAL_CONFIG_FOLDER)),
+            find_local_config_dir(&subdir, &[], &global_dirs)
+        );
+        let ceilings = vec![project.clone()];
+        assert_eq!(
+            Some(project.join(LOCAL_CONFIG_FOLDER)),
+            find_local_config_dir(&project, &ceilings, &global_dirs)
+        );
+        assert_eq!(
+            None,
+            find_local_config_dir(&subdir, &[project.join("src")], &global_dirs)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn never_find_the_global_config_dir() -> Result<()> {
+        let dir = tempdir()?;
+        let home = fs::canonicalize(dir.path())?;
+        let subdir = home.join("project");
+        fs::create_dir_all(&subdir)?;
+        fs::create_dir_all(home.join(LOCAL_CONFIG_FOLDER))?;
+
+        assert_eq!(
+            None,
+            find_local_config_dir(&subdir, &[], &[home.join(LOCAL_CONFIG_FOLDER)])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn config_option_wins() {
+        let local_config_dir = local_config_dir(Some(PathBuf::from("conf")), Path::new("global"));
+        assert_eq!(PathBuf::from("conf"), local_config_dir.path);
+        assert_eq!(ConfigOrigin::Option, local_config_dir.origin);
+        let global_config_dir = global_config_dir(Some(PathBuf::from("global")));
+        assert_eq!(PathBuf::from("global"), global_config_dir.path);
+        assert_eq!(GlobalConfigOrigin::Option, global_config_dir.origin);
+    }
+
+    #[test]
+    fn global_state_dir_follows_the_global_config_dir() {
+        let global_dir = PathBuf::from("/srv/wints");
+        assert_eq!(global_dir, global_state_dir(&global_dir));
+        assert!(global_state_dir(&xdg_config_dir()).ends_with(XDG_FOLDER));
+        assert_ne!(xdg_config_dir(), global_state_dir(&xdg_config_dir()));
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Current UTC date as YYYY-MM-DD
+pub fn today() -> String {
+    date_from_days((now_seconds() / 86_400) as i64)
+}
+
+// Current UTC date and time as YYYY-MM-DD HH:MM:SS
+pub fn now() -> String {
+    date_time_from_seconds(now_seconds())
+}
+
+fn now_seconds() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn date_time_from_seconds(seconds: u64) -> String {
+    format!(
+        "{} {:02}:{:02}:{:02}",
+        date_from_days((seconds / 86_400) as i64),
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60
+    )
+}
+
+pub fn is_valid_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    match parts.as_slice() {
+        [year, month, day] => {
+            year.len() == 4
+                && month.len() == 2
+                && day.len() == 2
+                && date
+                    .chars()
+                    .filter(|c| *c != '-')
+                    .all(|c| c.is_ascii_digit())
+                && (1..=12).contains(&month.parse::<u8>().unwrap_or(0))
+                && (1..=31).contains(&day.parse::<u8>().unwrap_or(0))
+        }
+        _ => false,
+    }
+}
+
+// Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+fn date_from_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::date::*;
+
+    #[test]
+    fn dates_from_days() {
+        assert_eq!("1970-01-01", date_from_days(0));
+        assert_eq!("2000-02-29", date_from_days(11_016));
+        assert_eq!("2024-01-07", date_from_days(19_729));
+    }
+
+    #[test]
+    fn date_times_from_seconds() {
+        assert_eq!("1970-01-01 00:00:00", date_time_from_seconds(0));
+        assert_eq!(
+            "2024-01-07 13:05:09",
+            date_time_from_seconds(19_729 * 86_400 + 13 * 3_600 + 5 * 60 + 9)
+        );
+        assert!(now().starts_with(&today()));
+    }
+
+    #[test]
+    fn valid_dates() {
+        assert!(is_valid_date("2024-01-07"));
+        assert!(is_valid_date(&today()));
+        assert!(!is_valid_date("2024-1-7"));
+        assert!(!is_valid_date("2024-13-01"));
+        assert!(!is_valid_date("tomorrow"));
+    }
+}
@@ -0,0 +1,453 @@
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use serde_json::Value;
+use zip::ZipArchive;
+
+use crate::util::html_links::decode_entities;
+
+// Render the visible text of a document which can't be searched as is (zip container, JSON),
+// hyperlinks are written as Markdown links so their text is kept as link text
+pub trait DocumentExtractor: Sync {
+    fn extensions(&self) -> &'static [&'static str];
+    fn extract(&self, content: &[u8]) -> Result<String>;
+}
+
+static EXTRACTORS: [&dyn DocumentExtractor; 5] = [
+    &WordExtractor,
+    &OpenDocumentExtractor,
+    &SpreadsheetExtractor,
+    &NotebookExtractor,
+    &EpubExtractor,
+];
+
+pub fn document_extractor(path: &Path) -> Option<&'static dyn DocumentExtractor> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    EXTRACTORS
+        .iter()
+        .find(|extractor| extractor.extensions().contains(&extension.as_str()))
+        .copied()
+}
+
+// How the text of a XML document is laid out
+struct XmlLayout {
+    // Elements ending a line
+    paragraphs: &'static [&'static str],
+    // Elements holding the visible text, all the text nodes are visible when empty
+    texts: &'static [&'static str],
+    // Element of a hyperlink and its attribute holding the target (or the relationship id)
+    link: (&'static str, &'static str),
+}
+
+const WORD_LAYOUT: XmlLayout = XmlLayout {
+    paragraphs: &["w:p"],
+    // Fields like 'HYPERLINK "https://..."' are in instrText
+    texts: &["w:t", "w:instrText"],
+    link: ("w:hyperlink", "r:id"),
+};
+
+const OPEN_DOCUMENT_LAYOUT: XmlLayout = XmlLayout {
+    paragraphs: &["text:p", "text:h", "table:table-cell"],
+    texts: &[],
+    link: ("text:a", "xlink:href"),
+};
+
+const SPREADSHEET_LAYOUT: XmlLayout = XmlLayout {
+    paragraphs: &["si", "c"],
+    texts: &["t"],
+    link: ("hyperlink", "r:id"),
+};
+
+const XHTML_LAYOUT: XmlLayout = XmlLayout {
+    paragraphs: &[
+        "p", "div", "li", "tr", "h1", "h2", "h3", "h4", "h5", "h6", "br",
+    ],
+    texts: &[],
+    link: ("a", "href"),
+};
+
+const WORD_PARTS: [&str; 6] = [
+    "word/document",
+    "word/header",
+    "word/footer",
+    "word/footnotes",
+    "word/endnotes",
+    "word/comments",
+];
+
+// .docx: the main document, its headers, footers and notes, links are relationships
+struct WordExtractor;
+
+impl DocumentExtractor for WordExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["docx", "docm", "dotx"]
+    }
+
+    fn extract(&self, content: &[u8]) -> Result<String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))?;
+        let parts = part_names(&archive, |name| {
+            WORD_PARTS
+                .iter()
+                .any(|part| name.starts_with(part) && name.ends_with(".xml"))
+        });
+        render_parts(&mut archive, &parts, &WORD_LAYOUT)
+    }
+}
+
+// .odt, .ods and .odp: everything is in content.xml, links are inline
+struct OpenDocumentExtractor;
+
+impl DocumentExtractor for OpenDocumentExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["odt", "ods", "odp"]
+    }
+
+    fn extract(&self, content: &[u8]) -> Result<String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))?;
+        render_parts(
+            &mut archive,
+            &["content.xml".to_string()],
+            &OPEN_DOCUMENT_LAYOUT,
+        )
+    }
+}
+
+// .xlsx: the shared strings, the inline strings and the hyperlinks of the sheets
+struct SpreadsheetExtractor;
+
+impl DocumentExtractor for SpreadsheetExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xlsx", "xlsm"]
+    }
+
+    fn extract(&self, content: &[u8]) -> Result<String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))?;
+        let parts = part_names(&archive, |name| {
+            name == "xl/sharedStrings.xml"
+                || (name.starts_with("xl/worksheets/") && name.ends_with(".xml"))
+        });
+        render_parts(&mut archive, &parts, &SPREADSHEET_LAYOUT)
+    }
+}
+
+// .epub: the XHTML chapters
+struct EpubExtractor;
+
+impl DocumentExtractor for EpubExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["epub"]
+    }
+
+    fn extract(&self, content: &[u8]) -> Result<String> {
+        let mut archive = ZipArchive::new(Cursor::new(content))?;
+        let parts = part_names(&archive, |name| {
+            name.ends_with(".xhtml") || name.ends_with(".html") || name.ends_with(".htm")
+        });
+        render_parts(&mut archive, &parts, &XHTML_LAYOUT)
+    }
+}
+
+// .ipynb: the sources of the cells and their text outputs
+struct NotebookExtractor;
+
+impl DocumentExtractor for NotebookExtractor {
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ipynb"]
+    }
+
+    fn extract(&self, content: &[u8]) -> Result<String> {
+        let notebook: Value = serde_json::from_slice(content)?;
+        let cells = notebook["cells"]
+            .as_array()
+            .ok_or_else(|| anyhow!("not a notebook, no cells"))?;
+        let mut lines = vec![];
+        for cell in cells {
+            lines.push(notebook_text(&cell["source"]));
+            for output in cell["outputs"].as_array().into_iter().flatten() {
+                lines.push(notebook_text(&output["text"]));
+                for mime_type in ["text/plain", "text/markdown", "text/html"] {
+                    lines.push(notebook_text(&output["data"][mime_type]));
+                }
+            }
+        }
+        lines.retain(|line| !line.is_empty());
+        Ok(lines.join("\n"))
+    }
+}
+
+// Multiline texts are either a string or a list of lines
+fn notebook_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(lines) => lines
+            .iter()
+            .filter_map(|line| line.as_str())
+            .collect::<String>(),
+        _ => String::new(),
+    }
+}
+
+fn part_names<F>(archive: &ZipArchive<Cursor<&[u8]>>, filter: F) -> Vec<String>
+where
+    F: Fn(&str) -> bool,
+{
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| filter(name))
+        .map(|name| name.to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+fn read_part(archive: &mut ZipArchive<Cursor<&[u8]>>, name: &str) -> Option<String> {
+    let mut part = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    part.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+fn render_parts(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    parts: &[String],
+    layout: &XmlLayout,
+) -> Result<String> {
+    let xml = XmlRenderer::new();
+    let mut rendered = vec![];
+    for name in parts {
+        let content = match read_part(archive, name) {
+            Some(content) => content,
+            None => continue,
+        };
+        let targets = match read_part(archive, &relationships_name(name)) {
+            Some(relationships) => xml.relationships(&relationships),
+            None => HashMap::new(),
+        };
+        let text = xml.render(&content, layout, &targets);
+        if !text.trim().is_empty() {
+            rendered.push(text);
+        }
+    }
+    Ok(rendered.join("\n"))
+}
+
+// Relationships of 'word/document.xml' are in 'word/_rels/document.xml.rels'
+fn relationships_name(name: &str) -> String {
+    match name.rsplit_once('/') {
+        Some((folder, file)) => format!("{}/_rels/{}.rels", folder, file),
+        None => format!("_rels/{}.rels", name),
+    }
+}
+
+struct XmlRenderer {
+    tokens: Regex,
+    attributes: Regex,
+}
+
+impl XmlRenderer {
+    fn new() -> XmlRenderer {
+        XmlRenderer {
+            tokens: Regex::new(r#"(?s)<(/?)([\w:.-]+)([^>]*?)(/?)>|<[^>]*>|[^<]+"#).unwrap(),
+            attributes: Regex::new(r#"([\w:.-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap(),
+        }
+    }
+
+    fn attribute(&self, attributes: &str, name: &str) -> Option<String> {
+        self.attributes
+            .captures_iter(attributes)
+            .find(|attribute| &attribute[1] == name)
+            .and_then(|attribute| attribute.get(2).or_else(|| attribute.get(3)))
+            .map(|value| decode_entities(value.as_str()))
+    }
+
+    // Relationship ids and their targets
+    fn relationships(&self, content: &str) -> HashMap<String, String> {
+        self.tokens
+            .captures_iter(content)
+            .filter(|token| token.get(2).map(|name| name.as_str()) == Some("Relationship"))
+            .filter_map(|token| {
+                let attributes = token.get(3)?.as_str();
+                Some((
+                    self.attribute(attributes, "Id")?,
+                    self.attribute(attributes, "Target")?,
+                ))
+            })
+            .collect()
+    }
+
+    fn render(
+        &self,
+        content: &str,
+        layout: &XmlLayout,
+        targets: &HashMap<String, String>,
+    ) -> String {
+        let mut rendered = String::new();
+        let mut text_depth = 0;
+        // Target of the current link and where its text starts
+        let mut link: Option<(String, usize)> = None;
+        for token in self.tokens.captures_iter(content) {
+            let name = match token.get(2) {
+                Some(name) => name.as_str(),
+                None => {
+                    let text = &token[0];
+                    if !text.starts_with('<') && (layout.texts.is_empty() || text_depth > 0) {
+                        rendered.push_str(&decode_entities(text));
+                    }
+                    continue;
+                }
+            };
+            let closing = !token[1].is_empty();
+            let self_closing = !token[4].is_empty();
+            if layout.texts.contains(&name) && !self_closing {
+                match closing {
+                    true => text_depth -= 1,
+                    false => text_depth += 1,
+                }
+            }
+            if name == layout.link.0 {
+                if closing {
+                    if let Some((target, start)) = link.take() {
+                        let text = rendered.split_off(start);
+                        rendered.push_str(&format!("[{}]({})", text.trim(), target));
+                    }
+                } else if let Some(target) = self.attribute(&token[3], layout.link.1) {
+                    let target = targets.get(&target).cloned().unwrap_or(target);
+                    match self_closing {
+                        true => rendered.push_str(&format!("\n{}\n", target)),
+                        false => link = Some((target, rendered.len())),
+                    }
+                }
+            }
+            if (closing || self_closing) && layout.paragraphs.contains(&name) && link.is_none() {
+                rendered.push('\n');
+            }
+        }
+        rendered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::path::Path;
+
+    use anyhow::Result;
+    use zip::write::FileOptions;
+    use zip::ZipWriter;
+
+    use crate::util::document_extractor::*;
+
+    fn zip_of(parts: &[(&str, &str)]) -> Result<Vec<u8>> {
+        let mut writer = ZipWriter::new(Cursor::new(vec![]));
+        for (name, content) in parts {
+            writer.start_file(*name, FileOptions::default())?;
+            writer.write_all(content.as_bytes())?;
+        }
+        Ok(writer.finish()?.into_inner())
+    }
+
+    fn extract(file_name: &str, content: &[u8]) -> String {
+        document_extractor(Path::new(file_name))
+            .unwrap()
+            .extract(content)
+            .unwrap()
+    }
+
+    #[test]
+    fn word_documents() -> Result<()> {
+        let document = zip_of(&[
+            (
+                "word/document.xml",
+                r#"<w:document><w:body>
+<w:p><w:r><w:t>See the </w:t></w:r><w:hyperlink r:id="rId5"><w:r><w:t>runbook</w:t></w:r></w:hyperlink></w:p>
+<w:p><w:r><w:t xml:space="preserve">Or https://visible.tld/?a=1&amp;b=2</w:t></w:r></w:p>
+</w:body></w:document>"#,
+            ),
+            (
+                "word/_rels/document.xml.rels",
+                r#"<Relationships><Relationship Id="rId5" Type="hyperlink" Target="https://runbook.tld/deploy" TargetMode="External"/></Relationships>"#,
+            ),
+        ])?;
+        assert_eq!(
+            "See the [runbook](https://runbook.tld/deploy)\nOr https://visible.tld/?a=1&b=2\n",
+            extract("runbook.docx", &document)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_documents() -> Result<()> {
+        let document = zip_of(&[(
+            "content.xml",
+            r#"<office:text><text:p>Go to <text:a xlink:href="https://site.tld">the site</text:a></text:p><text:p>https://other.tld</text:p></office:text>"#,
+        )])?;
+        assert_eq!(
+            "Go to [the site](https://site.tld)\nhttps://other.tld\n",
+            extract("notes.odt", &document)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn spreadsheets() -> Result<()> {
+        let document = zip_of(&[
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst><si><t>Dashboard https://grafana.tld</t></si><si><t>Name</t></si></sst>"#,
+            ),
+            (
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData><row><c t="s"><v>0</v></c></row></sheetData><hyperlinks><hyperlink ref="A1" r:id="rId1"/></hyperlinks></worksheet>"#,
+            ),
+            (
+                "xl/worksheets/_rels/sheet1.xml.rels",
+                r#"<Relationships><Relationship Id="rId1" Target="https://sheet.tld/link" TargetMode="External"/></Relationships>"#,
+            ),
+        ])?;
+        let text = extract("links.xlsx", &document);
+        assert!(text.contains("Dashboard https://grafana.tld\n"));
+        assert!(text.contains("\nhttps://sheet.tld/link\n"));
+        assert!(!text.contains("Name0"));
+        Ok(())
+    }
+
+    #[test]
+    fn epubs() -> Result<()> {
+        let document = zip_of(&[
+            ("mimetype", "application/epub+zip"),
+            (
+                "OEBPS/chapter1.xhtml",
+                r#"<html><body><p>Read <a href="https://book.tld">the book</a></p><p><a href="chapter2.xhtml">Next</a></p></body></html>"#,
+            ),
+        ])?;
+        assert_eq!(
+            "Read [the book](https://book.tld)\n[Next](chapter2.xhtml)\n",
+            extract("book.epub", &document)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn notebooks() {
+        let notebook = r##"{
+  "cells": [
+    {"cell_type": "markdown", "source": ["# Data\n", "From https://data.tld"]},
+    {"cell_type": "code", "source": "fetch()", "outputs": [{"output_type": "stream", "text": ["See https://logs.tld\n"]}]}
+  ]
+}"##;
+        assert_eq!(
+            "# Data\nFrom https://data.tld\nfetch()\nSee https://logs.tld\n",
+            extract("analysis.ipynb", notebook.as_bytes())
+        );
+    }
+
+    #[test]
+    fn invalid_documents() {
+        let extractor = document_extractor(Path::new("broken.docx")).unwrap();
+        assert!(extractor.extract(b"not a zip").is_err());
+        assert!(document_extractor(Path::new("README.md")).is_none());
+    }
+}
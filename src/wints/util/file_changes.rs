@@ -0,0 +1,150 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use similar::TextDiff;
+
+use crate::util::log::DRY_RUN;
+
+// A file written by the storage, or which would be in dry-run mode
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileChange {
+    pub path: PathBuf,
+    // None when the file is created
+    pub before: Option<String>,
+    // None when the file is deleted
+    pub after: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DryRunFormat {
+    // Unified diff of each file
+    #[default]
+    Diff,
+    // One JSON document, alone on stdout for review tools
+    Json,
+}
+
+#[derive(Serialize)]
+struct ChangeReport<'a> {
+    path: &'a PathBuf,
+    status: &'static str,
+    before: &'a Option<String>,
+    after: &'a Option<String>,
+    diff: String,
+}
+
+impl FileChange {
+    pub fn status(&self) -> &'static str {
+        match (&self.before, &self.after) {
+            (None, _) => "created",
+            (Some(_), None) => "deleted",
+            (Some(_), Some(_)) => "modified",
+        }
+    }
+
+    pub fn diff(&self) -> String {
+        let path = self.path.display().to_string();
+        let before = self.before.clone().unwrap_or_default();
+        let after = self.after.clone().unwrap_or_default();
+        let old_header = match self.before {
+            None => "/dev/null".to_string(),
+            Some(_) => format!("a/{}", path.trim_start_matches('/')),
+        };
+        let new_header = match self.after {
+            None => "/dev/null".to_string(),
+            Some(_) => format!("b/{}", path.trim_start_matches('/')),
+        };
+        TextDiff::from_lines(&before, &after)
+            .unified_diff()
+            .header(&old_header, &new_header)
+            .to_string()
+    }
+}
+
+pub fn report_changes(changes: &[FileChange], format: DryRunFormat) -> Result<()> {
+    match format {
+        DryRunFormat::Diff => {
+            for change in changes {
+                match change.after {
+                    Some(_) => println!(" {} Write '{}'", DRY_RUN, change.path.display()),
+                    None => println!(" {} Delete '{}'", DRY_RUN, change.path.display()),
+                }
+                print!("{}", change.diff());
+            }
+        }
+        DryRunFormat::Json => {
+            let reports: Vec<ChangeReport> = changes
+                .iter()
+                .map(|change| ChangeReport {
+                    path: &change.path,
+                    status: change.status(),
+                    before: &change.before,
+                    after: &change.after,
+                    diff: change.diff(),
+                })
+                .collect();
+            println!(
+                "{}",
+                serde_json::json!({ "dry_run": true, "changes": reports })
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::util::file_changes::*;
+
+    #[test]
+    fn diff_of_a_modified_file() {
+        let change = FileChange {
+            path: PathBuf::from(".wints/modules/main.yaml"),
+            before: Some("version: 1\nelements:\n- context: docs\n".to_string()),
+            after: Some("version: 1\nelements:\n- context: docs\n- context: ci\n".to_string()),
+        };
+        assert_eq!("modified", change.status());
+        assert_eq!(
+            "--- a/.wints/modules/main.yaml
++++ b/.wints/modules/main.yaml
+@@ -1,3 +1,4 @@
+ version: 1
+ elements:
+ - context: docs
++- context: ci
+",
+            change.diff()
+        );
+    }
+
+    #[test]
+    fn diff_of_a_created_file() {
+        let change = FileChange {
+            path: PathBuf::from("/home/user/.wints/ignore"),
+            before: None,
+            after: Some("target\n".to_string()),
+        };
+        assert_eq!("created", change.status());
+        assert_eq!(
+            "--- /dev/null\n+++ b/home/user/.wints/ignore\n@@ -0,0 +1 @@\n+target\n",
+            change.diff()
+        );
+    }
+
+    #[test]
+    fn diff_of_a_deleted_file() {
+        let change = FileChange {
+            path: PathBuf::from(".wints/ignore"),
+            before: Some("target\n".to_string()),
+            after: None,
+        };
+        assert_eq!("deleted", change.status());
+        assert_eq!(
+            "--- a/.wints/ignore\n+++ /dev/null\n@@ -1 +0,0 @@\n-target\n",
+            change.diff()
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::Write;
+use std::path;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Result};
+
+// Held by the process updating a configuration folder
+const LOCK_FILE: &str = ".lock";
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+// The content is written to a temporary file next to the target, then renamed over it,
+// so the target is never left half-written
+pub fn write_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let folder = path
+        .parent()
+        .ok_or_else(|| anyhow!("invalid path '{}'", path.display()))?;
+    fs::create_dir_all(folder)?;
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| anyhow!("invalid path '{}'", path.display()))?;
+    let temp_path = folder.join(format!(
+        ".{}.{}.tmp",
+        file_name.to_string_lossy(),
+        process::id()
+    ));
+    let written = File::create(&temp_path).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    match written.and_then(|_| fs::rename(&temp_path, path)) {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            let _ = fs::remove_file(&temp_path);
+            Err(anyhow!("unable to write '{}': {}", path.display(), err))
+        }
+    }
+}
+
+// Canonical path of an existing file or folder, absolute path otherwise
+pub fn resolve_path(path: &Path) -> PathBuf {
+    fs::canonicalize(path)
+        .or_else(|_| path::absolute(path))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Advisory lock on an existing configuration folder, released when the file is dropped
+pub fn lock_folder(folder: &Path) -> Result<File> {
+    let lock_path = folder.join(LOCK_FILE);
+    let lock_file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+    let started = Instant::now();
+    loop {
+        match lock_file.try_lock() {
+            Ok(()) => return Ok(lock_file),
+            Err(TryLockError::WouldBlock) if started.elapsed() < LOCK_TIMEOUT => {
+                thread::sleep(LOCK_RETRY_DELAY)
+            }
+            Err(TryLockError::WouldBlock) => {
+                return Err(anyhow!(
+                    "'{}' is locked by another wints command, try again once it is done",
+                    folder.display()
+                ))
+            }
+            Err(TryLockError::Error(err)) => {
+                return Err(anyhow!("unable to lock '{}': {}", folder.display(), err))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::util::file_store::*;
+
+    #[test]
+    fn write_over_a_file() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("modules").join("main.yaml");
+        write_atomically(&path, b"first")?;
+        write_atomically(&path, b"second")?;
+        assert_eq!("second", fs::read_to_string(&path)?);
+        assert_eq!(1, fs::read_dir(dir.path().join("modules"))?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn lock_a_folder_once() -> Result<()> {
+        let dir = tempdir()?;
+        let lock = lock_folder(dir.path())?;
+        let other_lock = File::open(dir.path().join(LOCK_FILE))?;
+        assert!(other_lock.try_lock().is_err());
+        drop(lock);
+        assert!(other_lock.try_lock().is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Result};
+
+use crate::util::date;
+use crate::util::url_scanner;
+use crate::util::url_scanner::ScannedUrl;
+
+// Separators which can't be found in a commit message
+const RECORD_SEPARATOR: char = '\x1e';
+const FIELD_SEPARATOR: char = '\0';
+
+// Sources found in the history are not files, they are never stale
+pub const GIT_SOURCE_PREFIX: &str = "git:";
+
+pub struct GitHistoryOptions {
+    pub repository: PathBuf,
+    // Only the commits since this date (YYYY-MM-DD)
+    pub since: Option<String>,
+    // Also scan the lines added or removed by each commit
+    pub diffs: bool,
+}
+
+// Sources are 'git:<commit>:<line>:<column>' for a message,
+// and 'git:<commit>:<file>:<line>:<column>' for a diff
+pub fn scan_git_history(options: &GitHistoryOptions) -> Result<Vec<ScannedUrl>> {
+    if let Some(since) = &options.since {
+        if !date::is_valid_date(since) {
+            return Err(anyhow!("invalid date '{}', expected YYYY-MM-DD", since));
+        }
+    }
+    let messages = git_log(options, &["--format=%h%x00%B%x1e"])?;
+    let mut lines = message_lines(&messages);
+    if options.diffs {
+        let diffs = git_log(
+            options,
+            &[
+                "--format=%x1e%h",
+                "--patch",
+                "--unified=0",
+                "--no-color",
+                "--no-ext-diff",
+            ],
+        )?;
+        lines.extend(diff_lines(&diffs));
+    }
+    Ok(url_scanner::scan_lines(lines))
+}
+
+// Root of the repository holding a path, to scan each history once
+pub fn repository_root(path: &Path) -> Result<PathBuf> {
+    let folder = match path.is_file() {
+        true => path.parent().unwrap_or(path),
+        false => path,
+    };
+    git(folder, &["rev-parse", "--show-toplevel"])
+        .map(|root| PathBuf::from(root.trim()))
+        .map_err(|_| anyhow!("'{}' is not in a git repository", path.display()))
+}
+
+fn git_log(options: &GitHistoryOptions, args: &[&str]) -> Result<String> {
+    let mut log_args = vec!["log".to_string()];
+    log_args.extend(args.iter().map(|arg| arg.to_string()));
+    if let Some(since) = &options.since {
+        log_args.push(format!("--since={}", since));
+    }
+    let log_args: Vec<&str> = log_args.iter().map(|arg| arg.as_str()).collect();
+    git(&options.repository, &log_args)
+}
+
+fn git(folder: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(folder)
+        .args(args)
+        .output()
+        .map_err(|err| anyhow!("git is not available: {}", err))?;
+    match output.status.success() {
+        true => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        false => Err(anyhow!(
+            "git {} failed: {}",
+            args.first().unwrap_or(&""),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+// 'git log --format=%h%x00%B%x1e' output, as lines of each message
+fn message_lines(log: &str) -> Vec<(PathBuf, u64, String)> {
+    let mut lines = vec![];
+    for record in log.split(RECORD_SEPARATOR) {
+        let (commit, message) = match record.trim_start().split_once(FIELD_SEPARATOR) {
+            Some(commit_and_message) => commit_and_message,
+            None => continue,
+        };
+        let path = PathBuf::from(format!("{}{}", GIT_SOURCE_PREFIX, commit));
+        for (index, line) in message.lines().enumerate() {
+            lines.push((path.clone(), index as u64 + 1, line.to_string()));
+        }
+    }
+    lines
+}
+
+// 'git log --format=%x1e%h --patch --unified=0' output, as added and removed lines
+fn diff_lines(log: &str) -> Vec<(PathBuf, u64, String)> {
+    let mut lines = vec![];
+    let mut commit = "";
+    let mut old_file = "";
+    let mut new_file = "";
+    let mut old_line = 0;
+    let mut new_line = 0;
+    // File headers are before the first hunk, a removed '-- text' line is not a header
+    let mut in_hunk = false;
+    for line in log.lines() {
+        if let Some(hash) = line.strip_prefix(RECORD_SEPARATOR) {
+            commit = hash.trim();
+            in_hunk = false;
+        } else if line.starts_with("diff ") {
+            in_hunk = false;
+        } else if let Some(hunk) = line.strip_prefix("@@ ") {
+            (old_line, new_line) = hunk_starts(hunk);
+            in_hunk = true;
+        } else if !in_hunk {
+            if let Some(file) = line.strip_prefix("--- ") {
+                old_file = file.strip_prefix("a/").unwrap_or(file);
+            } else if let Some(file) = line.strip_prefix("+++ ") {
+                new_file = file.strip_prefix("b/").unwrap_or(file);
+            }
+        } else if let Some(added) = line.strip_prefix('+') {
+            let path = PathBuf::from(format!("{}{}:{}", GIT_SOURCE_PREFIX, commit, new_file));
+            lines.push((path, new_line, added.to_string()));
+            new_line += 1;
+        } else if let Some(removed) = line.strip_prefix('-') {
+            let path = PathBuf::from(format!("{}{}:{}", GIT_SOURCE_PREFIX, commit, old_file));
+            lines.push((path, old_line, removed.to_string()));
+            old_line += 1;
+        }
+    }
+    lines
+}
+
+// '-12,3 +14,5 @@' gives the first removed and added lines
+fn hunk_starts(hunk: &str) -> (u64, u64) {
+    let start_of = |prefix: char| {
+        hunk.split_whitespace()
+            .find_map(|range| range.strip_prefix(prefix))
+            .and_then(|range| range.split(',').next())
+            .and_then(|start| start.parse().ok())
+            .unwrap_or_default()
+    };
+    (start_of('-'), start_of('+'))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::util::git_history::*;
+
+    #[test]
+    fn commit_messages() {
+        let log =
+            "abc1234\0Fix login\n\nSee https://issue.tld/42\n\x1e\ndef5678\0Initial commit\n\x1e\n";
+        let lines = message_lines(log);
+        assert_eq!(4, lines.len());
+        assert_eq!(
+            (
+                PathBuf::from("git:abc1234"),
+                3,
+                "See https://issue.tld/42".to_string()
+            ),
+            lines[2]
+        );
+        assert_eq!(PathBuf::from("git:def5678"), lines[3].0);
+    }
+
+    #[test]
+    fn diffs() {
+        let log = "\x1eabc1234
+
+diff --git a/docs/old.md b/docs/old.md
+deleted file mode 100644
+--- a/docs/old.md
++++ /dev/null
+@@ -3 +0,0 @@
+-Design at https://design.tld
+--- not a header
+diff --git a/README.md b/README.md
+--- a/README.md
++++ b/README.md
+@@ -10,0 +11,2 @@
++First line
++Go to https://new.tld
+";
+        let lines = diff_lines(log);
+        assert_eq!(
+            vec![
+                (
+                    PathBuf::from("git:abc1234:docs/old.md"),
+                    3,
+                    "Design at https://design.tld".to_string()
+                ),
+                (
+                    PathBuf::from("git:abc1234:docs/old.md"),
+                    4,
+                    "-- not a header".to_string()
+                ),
+                (
+                    PathBuf::from("git:abc1234:README.md"),
+                    11,
+                    "First line".to_string()
+                ),
+                (
+                    PathBuf::from("git:abc1234:README.md"),
+                    12,
+                    "Go to https://new.tld".to_string()
+                ),
+            ],
+            lines
+        );
+    }
+
+    #[test]
+    fn invalid_since_date() {
+        let options = GitHistoryOptions {
+            repository: PathBuf::from("."),
+            since: Some("last week".to_string()),
+            diffs: false,
+        };
+        assert!(scan_git_history(&options).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use url::Url;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GitRemote {
+    pub name: String,
+    pub url: String,
+}
+
+// Where a repository is hosted, like 'github.com' and 'rlespinasse/wints'
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemoteLocation {
+    pub host: String,
+    pub path: String,
+}
+
+// Remotes declared in the .git/config of the repository holding this folder
+pub fn read_remotes(folder: &Path) -> Result<Vec<GitRemote>> {
+    let config_path = git_config_path(folder)
+        .ok_or_else(|| anyhow!("'{}' is not in a git repository", folder.display()))?;
+    let config = fs::read_to_string(&config_path)?;
+    Ok(parse_remotes(&config))
+}
+
+// 'origin' is the repository, 'upstream' is the one it is forked from
+pub fn preferred_remote(remotes: &[GitRemote]) -> Option<&GitRemote> {
+    ["origin", "upstream"]
+        .iter()
+        .find_map(|name| remotes.iter().find(|remote| remote.name == *name))
+        .or_else(|| remotes.first())
+}
+
+// SSH (git@host:path, ssh://git@host:port/path) and HTTPS remote URLs
+pub fn parse_remote_url(remote_url: &str) -> Option<RemoteLocation> {
+    let remote_url = remote_url.trim();
+    let (host, path) = match remote_url.contains("://") {
+        true => {
+            let url = Url::parse(remote_url).ok()?;
+            (url.host_str()?.to_string(), url.path().to_string())
+        }
+        false => {
+            let (user_and_host, path) = remote_url.split_once(':')?;
+            let host = user_and_host.rsplit('@').next()?;
+            (host.to_string(), path.to_string())
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    match host.is_empty() || path.is_empty() {
+        true => None,
+        false => Some(RemoteLocation {
+            host: host.to_lowercase(),
+            path: path.to_string(),
+        }),
+    }
+}
+
+// .git is a folder, or a file pointing to it for worktrees and submodules
+fn git_config_path(folder: &Path) -> Option<PathBuf> {
+    let folder = fs::canonicalize(folder).unwrap_or_else(|_| folder.to_path_buf());
+    for ancestor in folder.ancestors() {
+        let dot_git = ancestor.join(".git");
+        if dot_git.is_dir() {
+            return Some(dot_git.join("config"));
+        }
+        if dot_git.is_file() {
+            let content = fs::read_to_string(&dot_git).ok()?;
+            let git_dir = ancestor.join(content.trim().strip_prefix("gitdir:")?.trim());
+            let common_dir = match fs::read_to_string(git_dir.join("commondir")) {
+                Ok(common_dir) => git_dir.join(common_dir.trim()),
+                Err(_) => git_dir,
+            };
+            return Some(common_dir.join("config"));
+        }
+    }
+    None
+}
+
+fn parse_remotes(config: &str) -> Vec<GitRemote> {
+    let mut remotes = vec![];
+    let mut remote_name: Option<String> = None;
+    for line in config.lines().map(|line| line.trim()) {
+        if line.starts_with('[') {
+            remote_name = line
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .strip_prefix("remote ")
+                .map(|name| name.trim().trim_matches('"').to_string());
+            continue;
+        }
+        let name = match &remote_name {
+            Some(name) => name,
+            None => continue,
+        };
+        if let Some((key, value)) = line.split_once('=') {
+            if key.trim() == "url" {
+                remotes.push(GitRemote {
+                    name: name.clone(),
+                    url: value.trim().trim_matches('"').to_string(),
+                });
+            }
+        }
+    }
+    remotes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::util::git_remote::*;
+
+    fn location(host: &str, path: &str) -> Option<RemoteLocation> {
+        Some(RemoteLocation {
+            host: host.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    #[test]
+    fn remote_urls() {
+        assert_eq!(
+            location("github.com", "rlespinasse/wints"),
+            parse_remote_url("git@github.com:rlespinasse/wints.git")
+        );
+        assert_eq!(
+            location("github.com", "rlespinasse/wints"),
+            parse_remote_url("https://github.com/rlespinasse/wints")
+        );
+        assert_eq!(
+            location("gitlab.com", "group/sub/project"),
+            parse_remote_url("ssh://git@gitlab.com:2222/group/sub/project.git")
+        );
+        assert_eq!(
+            location("bitbucket.org", "team/repo"),
+            parse_remote_url("https://user@bitbucket.org/team/repo.git/")
+        );
+        assert_eq!(None, parse_remote_url("/srv/git/repo.git"));
+        assert_eq!(None, parse_remote_url("https://github.com/"));
+    }
+
+    #[test]
+    fn remotes_of_a_repository() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join(".git"))?;
+        fs::create_dir_all(dir.path().join("docs"))?;
+        fs::write(
+            dir.path().join(".git").join("config"),
+            r#"[core]
+	bare = false
+[remote "upstream"]
+	url = https://github.com/rlespinasse/wints.git
+	fetch = +refs/heads/*:refs/remotes/upstream/*
+[remote "origin"]
+	url = git@github.com:someone/wints.git
+[branch "main"]
+	remote = origin
+"#,
+        )?;
+
+        let remotes = read_remotes(&dir.path().join("docs"))?;
+        assert_eq!(2, remotes.len());
+        assert_eq!(
+            "git@github.com:someone/wints.git",
+            preferred_remote(&remotes).unwrap().url
+        );
+
+        let other_dir = tempdir()?;
+        assert!(read_remotes(other_dir.path()).is_err());
+        Ok(())
+    }
+}
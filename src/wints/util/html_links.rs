@@ -0,0 +1,292 @@
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use url::Url;
+
+// Tags linking to another page or resource
+const LINK_TAGS: &str = r#"(?is)<(a|area|link|script|img|iframe|base)\b([^>]*)>"#;
+const LINK_ATTRIBUTE: &str = r#"(?is)\b(?:href|src)\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'>]+))"#;
+const TAGS: &str = r#"(?s)<[^>]*>"#;
+
+pub const HTML_EXTENSIONS: [&str; 3] = ["html", "htm", "xhtml"];
+
+pub struct HtmlLink {
+    pub url: String,
+    // Byte offset of the attribute value in the content
+    pub offset: usize,
+    pub link_text: Option<String>,
+}
+
+// Matchers are compiled once and shared by all the walking threads
+pub struct HtmlLinkFinder {
+    link_tags: Regex,
+    link_attribute: Regex,
+    tags: Regex,
+}
+
+impl HtmlLinkFinder {
+    pub fn new() -> HtmlLinkFinder {
+        HtmlLinkFinder {
+            link_tags: Regex::new(LINK_TAGS).unwrap(),
+            link_attribute: Regex::new(LINK_ATTRIBUTE).unwrap(),
+            tags: Regex::new(TAGS).unwrap(),
+        }
+    }
+
+    // Relative links are resolved against <base href>, then against the URL of the page
+    pub fn links(&self, content: &str, page_url: Option<&Url>) -> Vec<HtmlLink> {
+        let base_url = self.base_url(content, page_url);
+        let mut links = vec![];
+        for tag in self.link_tags.captures_iter(content) {
+            let name = tag[1].to_lowercase();
+            if name == "base" {
+                continue;
+            }
+            let attributes = tag.get(2).unwrap();
+            let (url, offset) = match self.attribute_value(attributes.as_str()) {
+                Some((value, offset)) => match resolve(value, base_url.as_ref()) {
+                    Some(url) => (url, offset),
+                    None => continue,
+                },
+                None => continue,
+            };
+            let link_text = match name.as_str() {
+                "a" => self.anchor_text(&content[tag.get(0).unwrap().end()..]),
+                _ => None,
+            };
+            links.push(HtmlLink {
+                url,
+                offset: attributes.start() + offset,
+                link_text,
+            });
+        }
+        links
+    }
+
+    // Blank the tags so only the URLs written in the text are left, lines and columns are kept
+    pub fn mask_tags(&self, content: &str) -> String {
+        self.tags
+            .replace_all(content, |tag: &regex::Captures| {
+                tag[0]
+                    .chars()
+                    .map(|c| match c {
+                        '\n' => "\n".to_string(),
+                        _ => " ".repeat(c.len_utf8()),
+                    })
+                    .collect::<String>()
+            })
+            .into_owned()
+    }
+
+    fn base_url(&self, content: &str, page_url: Option<&Url>) -> Option<Url> {
+        let base_href = self
+            .link_tags
+            .captures_iter(content)
+            .find(|tag| tag[1].eq_ignore_ascii_case("base"))
+            .and_then(|tag| {
+                self.attribute_value(tag.get(2).unwrap().as_str())
+                    .map(|(value, _)| decode_entities(value.trim()))
+            });
+        match base_href {
+            None => page_url.cloned(),
+            Some(base_href) => match Url::parse(&base_href) {
+                Ok(base_url) => Some(base_url),
+                Err(_) => page_url.and_then(|page_url| page_url.join(&base_href).ok()),
+            },
+        }
+    }
+
+    fn attribute_value<'a>(&self, attributes: &'a str) -> Option<(&'a str, usize)> {
+        let attribute = self.link_attribute.captures(attributes)?;
+        let value = (1..=3).find_map(|index| attribute.get(index))?;
+        Some((value.as_str(), value.start()))
+    }
+
+    fn anchor_text(&self, after_tag: &str) -> Option<String> {
+        let end = after_tag.to_ascii_lowercase().find("</a")?;
+        let text = self.tags.replace_all(&after_tag[..end], " ");
+        let text = decode_entities(&text)
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ");
+        match text.is_empty() {
+            true => None,
+            false => Some(text),
+        }
+    }
+}
+
+impl Default for HtmlLinkFinder {
+    fn default() -> HtmlLinkFinder {
+        HtmlLinkFinder::new()
+    }
+}
+
+// The site root is a folder, 'https://site.tld/docs' is read as 'https://site.tld/docs/'
+pub fn parse_site_root(site_root: &str) -> Result<Url> {
+    let mut url = Url::parse(site_root)
+        .map_err(|err| anyhow!("invalid site root '{}': {}", site_root, err))?;
+    if url.cannot_be_a_base() {
+        return Err(anyhow!("invalid site root '{}'", site_root));
+    }
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+    Ok(url)
+}
+
+// Only the entities used in URLs and link texts, unknown ones are kept as is
+pub fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        decoded.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest
+            .char_indices()
+            .take(12)
+            .find(|(_, c)| *c == ';')
+            .and_then(|(end, _)| decode_entity(&rest[1..end]).map(|c| (c, end)));
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
+
+fn decode_entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "nbsp" => Some('\u{a0}'),
+        _ => {
+            let code = match name.strip_prefix("#x").or_else(|| name.strip_prefix("#X")) {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => name.strip_prefix('#')?.parse().ok()?,
+            };
+            char::from_u32(code)
+        }
+    }
+}
+
+// Only web links are kept, anchors in the same page are not links to another page
+fn resolve(value: &str, base_url: Option<&Url>) -> Option<String> {
+    let value = decode_entities(value.trim());
+    if value.is_empty() || value.starts_with('#') {
+        return None;
+    }
+    let url = match Url::parse(&value) {
+        Ok(url) => match url.scheme() {
+            "http" | "https" => return Some(value),
+            _ => return None,
+        },
+        Err(url::ParseError::RelativeUrlWithoutBase) => base_url?.join(&value).ok()?,
+        Err(_) => return None,
+    };
+    match url.scheme() {
+        "http" | "https" => Some(url.to_string()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use url::Url;
+
+    use crate::util::html_links::*;
+
+    fn urls(content: &str, page_url: Option<&str>) -> Vec<String> {
+        let page_url = page_url.map(|page_url| Url::parse(page_url).unwrap());
+        HtmlLinkFinder::new()
+            .links(content, page_url.as_ref())
+            .into_iter()
+            .map(|link| link.url)
+            .collect()
+    }
+
+    #[test]
+    fn absolute_links() {
+        let content = r#"
+<link rel="stylesheet" href='https://cdn.tld/style.css'>
+<script src=https://cdn.tld/app.js></script>
+<a class="x" HREF="https://site.tld/search?q=1&amp;page=2">Search</a>
+"#;
+        assert_eq!(
+            vec![
+                "https://cdn.tld/style.css",
+                "https://cdn.tld/app.js",
+                "https://site.tld/search?q=1&page=2"
+            ],
+            urls(content, None)
+        );
+    }
+
+    #[test]
+    fn relative_links() {
+        let content = r##"<a href="../guide/">Guide</a> <a href="#top">Top</a> <a href="mailto:me@site.tld">Me</a>"##;
+        assert!(urls(content, None).is_empty());
+        assert_eq!(
+            vec!["https://site.tld/docs/guide/"],
+            urls(content, Some("https://site.tld/docs/api/index.html"))
+        );
+    }
+
+    #[test]
+    fn relative_links_with_base() {
+        let content = r#"<base href="https://other.tld/v2/"><a href="intro.html">Intro</a>"#;
+        assert_eq!(vec!["https://other.tld/v2/intro.html"], urls(content, None));
+
+        let content = r#"<base href="/v2/"><a href="intro.html">Intro</a>"#;
+        assert_eq!(
+            vec!["https://site.tld/v2/intro.html"],
+            urls(content, Some("https://site.tld/docs/index.html"))
+        );
+    }
+
+    #[test]
+    fn link_text_and_offset() {
+        let content = "<p>\n  <a href=\"https://site.tld\"><b>Release</b> &amp; notes</a>\n</p>";
+        let links = HtmlLinkFinder::new().links(content, None);
+        assert_eq!(1, links.len());
+        assert_eq!(Some("Release & notes".to_string()), links[0].link_text);
+        assert_eq!(content.find("https").unwrap(), links[0].offset);
+    }
+
+    #[test]
+    fn mask_tags() {
+        let content = "<a href=\"https://site.tld\">\nSee https://text.tld</a>";
+        let masked = HtmlLinkFinder::new().mask_tags(content);
+        assert!(!masked.contains("https://site.tld"));
+        assert_eq!(
+            content.find("https://text.tld"),
+            masked.find("https://text.tld")
+        );
+        assert_eq!(content.len(), masked.len());
+    }
+
+    #[test]
+    fn entities() {
+        assert_eq!("a&b<c>", decode_entities("a&amp;b&lt;c&#62;"));
+        assert_eq!("/a/b", decode_entities("&#x2F;a&#47;b"));
+        assert_eq!("R&D &unknown;", decode_entities("R&D &unknown;"));
+    }
+
+    #[test]
+    fn site_roots() {
+        assert_eq!(
+            "https://site.tld/docs/",
+            parse_site_root("https://site.tld/docs").unwrap().as_str()
+        );
+        assert!(parse_site_root("docs").is_err());
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 pub static CAUTION: &str = "⚠️";
 pub static INFO: &str = "ℹ️";
 pub static SAD: &str = "😢";
@@ -8,6 +10,30 @@ pub static WRITE: &str = "📝";
 pub static TRY: &str = "🧭";
 pub static DONE: &str = "✅";
 pub static GOTO: &str = "↗️";
+pub static LOCATION: &str = "📍";
+pub static ASK: &str = "❓";
+
+// Set when stdout is kept for a document, like the JSON of '--dry-run --json'
+static LOGS_TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+pub fn set_logs_to_stderr(enabled: bool) {
+    LOGS_TO_STDERR.store(enabled, Ordering::Relaxed);
+}
+
+pub fn logs_to_stderr() -> bool {
+    LOGS_TO_STDERR.load(Ordering::Relaxed)
+}
+
+// Like println!, on stderr when stdout is kept for a document
+#[macro_export]
+macro_rules! log_line {
+    ($($arg:tt)*) => {
+        match $crate::util::log::logs_to_stderr() {
+            true => eprintln!($($arg)*),
+            false => println!($($arg)*),
+        }
+    };
+}
 
 #[cfg(test)]
 mod tests {
@@ -57,4 +83,14 @@ mod tests {
     fn goto_output() {
         assert_eq!("[↗️]", format!("[{}]", GOTO));
     }
+
+    #[test]
+    fn location_output() {
+        assert_eq!("[📍]", format!("[{}]", LOCATION));
+    }
+
+    #[test]
+    fn ask_output() {
+        assert_eq!("[❓]", format!("[{}]", ASK));
+    }
 }
@@ -0,0 +1,469 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use serde_json::Value as JsonValue;
+use toml::Value as TomlValue;
+
+pub const MANIFEST_NAMES: [&str; 4] = ["Cargo.toml", "package.json", "pyproject.toml", "go.mod"];
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ecosystem {
+    Cargo,
+    Npm,
+    Python,
+    Go,
+}
+
+// What a manifest declares about its project
+#[derive(Clone, Debug, PartialEq)]
+pub struct Manifest {
+    pub path: PathBuf,
+    pub ecosystem: Ecosystem,
+    pub name: Option<String>,
+    pub homepage: Option<String>,
+    pub repository: Option<String>,
+    pub documentation: Option<String>,
+    // Other project URLs, like the 'project.urls' of a pyproject.toml
+    pub urls: Vec<(String, String)>,
+    // Direct dependencies, the development ones are left out
+    pub dependencies: Vec<String>,
+}
+
+impl Manifest {
+    fn new(path: &Path, ecosystem: Ecosystem) -> Manifest {
+        Manifest {
+            path: path.to_path_buf(),
+            ecosystem,
+            name: None,
+            homepage: None,
+            repository: None,
+            documentation: None,
+            urls: vec![],
+            dependencies: vec![],
+        }
+    }
+
+    // Contexts and URLs, like ('crate docs', 'https://docs.rs/wints')
+    pub fn contexts(&self) -> Vec<(String, String)> {
+        let kind = match self.ecosystem {
+            Ecosystem::Cargo => "crate",
+            Ecosystem::Npm | Ecosystem::Python => "package",
+            Ecosystem::Go => "module",
+        };
+        let mut contexts: Vec<(String, String)> = vec![];
+        let mut add = |context: String, url: Option<String>| {
+            if let Some(url) = url.filter(|url| url.starts_with("http")) {
+                if !contexts.iter().any(|(_, known_url)| *known_url == url) {
+                    contexts.push((context, url));
+                }
+            }
+        };
+        if let Some(name) = &self.name {
+            add(format!("{} page", kind), package_page(self.ecosystem, name));
+            add(
+                format!("{} docs", kind),
+                self.documentation
+                    .clone()
+                    .or_else(|| package_docs(self.ecosystem, name)),
+            );
+        }
+        add(format!("{} homepage", kind), self.homepage.clone());
+        add(format!("{} repository", kind), self.repository.clone());
+        for (label, url) in &self.urls {
+            add(
+                format!("{} {}", kind, label.to_lowercase()),
+                Some(url.clone()),
+            );
+        }
+        for dependency in &self.dependencies {
+            add(
+                format!("dependency {} docs", dependency),
+                package_docs(self.ecosystem, dependency),
+            );
+        }
+        contexts
+    }
+}
+
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .map(|name| MANIFEST_NAMES.contains(&name))
+        .unwrap_or(false)
+}
+
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let content = fs::read_to_string(path)?;
+    let file_name = path.file_name().and_then(|name| name.to_str());
+    match file_name {
+        Some("Cargo.toml") => parse_cargo(path, &content),
+        Some("package.json") => parse_package_json(path, &content),
+        Some("pyproject.toml") => parse_pyproject(path, &content),
+        Some("go.mod") => Ok(parse_go_mod(path, &content)),
+        _ => Err(anyhow!("'{}' is not a known manifest", path.display())),
+    }
+    .map_err(|err| anyhow!("invalid manifest '{}': {}", path.display(), err))
+}
+
+fn package_page(ecosystem: Ecosystem, name: &str) -> Option<String> {
+    match ecosystem {
+        Ecosystem::Cargo => Some(format!("https://crates.io/crates/{}", name)),
+        Ecosystem::Npm => Some(format!("https://www.npmjs.com/package/{}", name)),
+        Ecosystem::Python => Some(format!("https://pypi.org/project/{}", name)),
+        Ecosystem::Go => None,
+    }
+}
+
+fn package_docs(ecosystem: Ecosystem, name: &str) -> Option<String> {
+    match ecosystem {
+        Ecosystem::Cargo => Some(format!("https://docs.rs/{}", name)),
+        Ecosystem::Npm => Some(format!("https://www.npmjs.com/package/{}", name)),
+        Ecosystem::Python => Some(format!("https://pypi.org/project/{}", name)),
+        Ecosystem::Go => Some(format!("https://pkg.go.dev/{}", name)),
+    }
+}
+
+fn toml_string(value: &TomlValue, key: &str) -> Option<String> {
+    value.get(key)?.as_str().map(|value| value.to_string())
+}
+
+fn parse_cargo(path: &Path, content: &str) -> Result<Manifest> {
+    let cargo: TomlValue = toml::from_str(content)?;
+    let mut manifest = Manifest::new(path, Ecosystem::Cargo);
+    if let Some(package) = cargo.get("package") {
+        manifest.name = toml_string(package, "name");
+        manifest.homepage = toml_string(package, "homepage");
+        manifest.repository = toml_string(package, "repository");
+        manifest.documentation = toml_string(package, "documentation");
+    }
+    let dependency_tables = [
+        cargo.get("dependencies"),
+        cargo.get("build-dependencies"),
+        cargo
+            .get("workspace")
+            .and_then(|workspace| workspace.get("dependencies")),
+    ];
+    for table in dependency_tables.into_iter().flatten() {
+        for (name, dependency) in table.as_table().into_iter().flatten() {
+            // Renamed dependencies, like 'yaml = { package = "serde_yaml" }'
+            let name = toml_string(dependency, "package").unwrap_or_else(|| name.clone());
+            if !manifest.dependencies.contains(&name) {
+                manifest.dependencies.push(name);
+            }
+        }
+    }
+    Ok(manifest)
+}
+
+fn parse_package_json(path: &Path, content: &str) -> Result<Manifest> {
+    let package: JsonValue = serde_json::from_str(content)?;
+    let mut manifest = Manifest::new(path, Ecosystem::Npm);
+    manifest.name = package["name"].as_str().map(|name| name.to_string());
+    manifest.homepage = package["homepage"].as_str().map(|url| url.to_string());
+    // A string or { "type": "git", "url": "..." }
+    manifest.repository = package["repository"]
+        .as_str()
+        .or_else(|| package["repository"]["url"].as_str())
+        .map(repository_web_url);
+    manifest.urls = package["bugs"]["url"]
+        .as_str()
+        .or_else(|| package["bugs"].as_str())
+        .map(|url| vec![("bugs".to_string(), url.to_string())])
+        .unwrap_or_default();
+    manifest.dependencies = package["dependencies"]
+        .as_object()
+        .map(|dependencies| dependencies.keys().cloned().collect())
+        .unwrap_or_default();
+    Ok(manifest)
+}
+
+fn parse_pyproject(path: &Path, content: &str) -> Result<Manifest> {
+    let pyproject: TomlValue = toml::from_str(content)?;
+    let mut manifest = Manifest::new(path, Ecosystem::Python);
+    if let Some(project) = pyproject.get("project") {
+        manifest.name = toml_string(project, "name");
+        for (label, url) in project
+            .get("urls")
+            .and_then(|urls| urls.as_table())
+            .into_iter()
+            .flatten()
+        {
+            let url = match url.as_str() {
+                Some(url) => url.to_string(),
+                None => continue,
+            };
+            match label.to_lowercase().as_str() {
+                "homepage" => manifest.homepage = Some(url),
+                "repository" | "source" | "source code" => manifest.repository = Some(url),
+                "documentation" | "docs" => manifest.documentation = Some(url),
+                _ => manifest.urls.push((label.clone(), url)),
+            }
+        }
+        // PEP 508 requirements, like 'requests[socks]>=2.31; python_version>"3.8"'
+        for requirement in project
+            .get("dependencies")
+            .and_then(|dependencies| dependencies.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|requirement| requirement.as_str())
+        {
+            let name: String = requirement
+                .trim()
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+                .collect();
+            if !name.is_empty() {
+                manifest.dependencies.push(name);
+            }
+        }
+    }
+    if let Some(poetry) = pyproject.get("tool").and_then(|tool| tool.get("poetry")) {
+        manifest.name = manifest.name.or_else(|| toml_string(poetry, "name"));
+        manifest.homepage = manifest
+            .homepage
+            .or_else(|| toml_string(poetry, "homepage"));
+        manifest.repository = manifest
+            .repository
+            .or_else(|| toml_string(poetry, "repository"));
+        manifest.documentation = manifest
+            .documentation
+            .or_else(|| toml_string(poetry, "documentation"));
+        for name in poetry
+            .get("dependencies")
+            .and_then(|dependencies| dependencies.as_table())
+            .into_iter()
+            .flat_map(|dependencies| dependencies.keys())
+        {
+            if name != "python" && !manifest.dependencies.contains(name) {
+                manifest.dependencies.push(name.clone());
+            }
+        }
+    }
+    Ok(manifest)
+}
+
+// 'module' and the 'require' directives, the indirect requirements are left out
+fn parse_go_mod(path: &Path, content: &str) -> Manifest {
+    let mut manifest = Manifest::new(path, Ecosystem::Go);
+    let mut in_require_block = false;
+    for line in content.lines().map(|line| line.trim()) {
+        let requirement = match line {
+            ")" => {
+                in_require_block = false;
+                continue;
+            }
+            "require (" => {
+                in_require_block = true;
+                continue;
+            }
+            _ if in_require_block => line,
+            _ => match line.strip_prefix("module ") {
+                Some(module) => {
+                    manifest.name = Some(module.trim().trim_matches('"').to_string());
+                    continue;
+                }
+                None => match line.strip_prefix("require ") {
+                    Some(requirement) => requirement,
+                    None => continue,
+                },
+            },
+        };
+        if requirement.contains("// indirect") {
+            continue;
+        }
+        if let Some(module) = requirement.split_whitespace().next() {
+            if !module.starts_with("//") {
+                manifest.dependencies.push(module.to_string());
+            }
+        }
+    }
+    manifest.repository = manifest
+        .name
+        .as_ref()
+        .filter(|module| {
+            ["github.com/", "gitlab.com/", "bitbucket.org/"]
+                .iter()
+                .any(|host| module.starts_with(host))
+        })
+        .map(|module| format!("https://{}", module));
+    manifest
+}
+
+// 'git+https://github.com/user/repo.git', 'github:user/repo' or 'user/repo'
+fn repository_web_url(repository: &str) -> String {
+    let repository = repository.trim();
+    let repository = repository.strip_prefix("git+").unwrap_or(repository);
+    let repository = repository.strip_suffix(".git").unwrap_or(repository);
+    if let Some(path) = repository.strip_prefix("github:") {
+        return format!("https://github.com/{}", path);
+    }
+    if let Some(path) = repository.strip_prefix("gitlab:") {
+        return format!("https://gitlab.com/{}", path);
+    }
+    if let Some(path) = repository.strip_prefix("bitbucket:") {
+        return format!("https://bitbucket.org/{}", path);
+    }
+    if let Some(path) = repository.strip_prefix("git://") {
+        return format!("https://{}", path);
+    }
+    if !repository.contains(':') && repository.matches('/').count() == 1 {
+        return format!("https://github.com/{}", repository);
+    }
+    repository.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::util::manifests::*;
+
+    fn contexts(file_name: &str, content: &str) -> Vec<(String, String)> {
+        let manifest = match file_name {
+            "Cargo.toml" => parse_cargo(Path::new(file_name), content).unwrap(),
+            "package.json" => parse_package_json(Path::new(file_name), content).unwrap(),
+            "pyproject.toml" => parse_pyproject(Path::new(file_name), content).unwrap(),
+            _ => parse_go_mod(Path::new(file_name), content),
+        };
+        manifest.contexts()
+    }
+
+    fn context(context: &str, url: &str) -> (String, String) {
+        (context.to_string(), url.to_string())
+    }
+
+    #[test]
+    fn cargo_manifest() {
+        let result = contexts(
+            "Cargo.toml",
+            r#"
+[package]
+name = "wints"
+repository = "https://github.com/rlespinasse/wints"
+
+[dependencies]
+serde = { version = "1.0", features = ["derive"] }
+yaml = { package = "serde_yaml", version = "0.9" }
+
+[dev-dependencies]
+tempfile = "3.10"
+"#,
+        );
+        assert_eq!(
+            vec![
+                context("crate page", "https://crates.io/crates/wints"),
+                context("crate docs", "https://docs.rs/wints"),
+                context("crate repository", "https://github.com/rlespinasse/wints"),
+                context("dependency serde docs", "https://docs.rs/serde"),
+                context("dependency serde_yaml docs", "https://docs.rs/serde_yaml"),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn npm_manifest() {
+        let result = contexts(
+            "package.json",
+            r#"{
+  "name": "@scope/app",
+  "homepage": "https://app.dev",
+  "repository": { "type": "git", "url": "git+https://github.com/scope/app.git" },
+  "dependencies": { "react": "^18.0.0" },
+  "devDependencies": { "jest": "^29.0.0" }
+}"#,
+        );
+        assert_eq!(
+            vec![
+                context("package page", "https://www.npmjs.com/package/@scope/app"),
+                context("package homepage", "https://app.dev"),
+                context("package repository", "https://github.com/scope/app"),
+                context(
+                    "dependency react docs",
+                    "https://www.npmjs.com/package/react"
+                ),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn python_manifest() {
+        let result = contexts(
+            "pyproject.toml",
+            r#"
+[project]
+name = "tool"
+dependencies = ["requests[socks]>=2.31; python_version>'3.8'", "click"]
+
+[project.urls]
+Documentation = "https://tool.readthedocs.io"
+Changelog = "https://tool.dev/changes"
+"#,
+        );
+        assert_eq!(
+            vec![
+                context("package page", "https://pypi.org/project/tool"),
+                context("package docs", "https://tool.readthedocs.io"),
+                context("package changelog", "https://tool.dev/changes"),
+                context(
+                    "dependency requests docs",
+                    "https://pypi.org/project/requests"
+                ),
+                context("dependency click docs", "https://pypi.org/project/click"),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn go_manifest() {
+        let result = contexts(
+            "go.mod",
+            r#"
+module github.com/team/service
+
+go 1.22
+
+require github.com/spf13/cobra v1.8.0
+
+require (
+	golang.org/x/text v0.14.0
+	github.com/inconshreveable/mousetrap v1.1.0 // indirect
+)
+"#,
+        );
+        assert_eq!(
+            vec![
+                context("module docs", "https://pkg.go.dev/github.com/team/service"),
+                context("module repository", "https://github.com/team/service"),
+                context(
+                    "dependency github.com/spf13/cobra docs",
+                    "https://pkg.go.dev/github.com/spf13/cobra"
+                ),
+                context(
+                    "dependency golang.org/x/text docs",
+                    "https://pkg.go.dev/golang.org/x/text"
+                ),
+            ],
+            result
+        );
+    }
+
+    #[test]
+    fn repository_urls() {
+        assert_eq!(
+            "https://github.com/user/repo",
+            repository_web_url("github:user/repo")
+        );
+        assert_eq!(
+            "https://github.com/user/repo",
+            repository_web_url("user/repo")
+        );
+        assert_eq!(
+            "https://gitlab.com/user/repo",
+            repository_web_url("git+https://gitlab.com/user/repo.git")
+        );
+    }
+}
@@ -1,2 +1,17 @@
+pub mod config_dirs;
+pub mod date;
+pub mod document_extractor;
+pub mod file_changes;
+pub mod file_store;
+pub mod git_history;
+pub mod git_remote;
+pub mod html_links;
 pub mod log;
+pub mod manifests;
+pub mod noise_filter;
+pub mod scan_cache;
+pub mod scan_selection;
+pub mod source_comments;
+pub mod url_normaliser;
 pub mod url_scanner;
+pub mod yaml_edit;
@@ -0,0 +1,308 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+use url::{Host, Url};
+
+// Curated sets of URLs which are found everywhere but never worth a context
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NoiseFilter {
+    Placeholder,
+    PrivateNetwork,
+    XmlNamespace,
+    License,
+    Registry,
+}
+
+pub const ALL_NOISE_FILTERS: [NoiseFilter; 5] = [
+    NoiseFilter::Placeholder,
+    NoiseFilter::PrivateNetwork,
+    NoiseFilter::XmlNamespace,
+    NoiseFilter::License,
+    NoiseFilter::Registry,
+];
+
+// Reserved names (RFC 2606 / RFC 6761) and usual fixtures
+const PLACEHOLDER_DOMAINS: [&str; 7] = [
+    "example.com",
+    "example.net",
+    "example.org",
+    "example.edu",
+    "yourdomain.com",
+    "your-domain.com",
+    "mydomain.com",
+];
+// localhost is part of the private network filter
+const PLACEHOLDER_TLDS: [&str; 4] = ["test", "example", "invalid", "tld"];
+
+// Hosts (and path prefixes) of schemas and namespaces identifiers
+const XML_NAMESPACES: [(&str, &str); 11] = [
+    ("schemas.xmlsoap.org", "/"),
+    ("schemas.openxmlformats.org", "/"),
+    ("schemas.microsoft.com", "/"),
+    ("schemas.android.com", "/"),
+    ("xmlns.com", "/"),
+    ("purl.org", "/dc/"),
+    ("json-schema.org", "/draft"),
+    ("maven.apache.org", "/POM/"),
+    ("maven.apache.org", "/xsd/"),
+    ("www.springframework.org", "/schema/"),
+    ("www.w3.org", "/XML/"),
+];
+
+const LICENSES: [(&str, &str); 8] = [
+    ("spdx.org", "/licenses"),
+    ("www.apache.org", "/licenses"),
+    ("opensource.org", "/licenses"),
+    ("www.gnu.org", "/licenses"),
+    ("creativecommons.org", "/licenses"),
+    ("creativecommons.org", "/publicdomain"),
+    ("choosealicense.com", "/"),
+    ("mit-license.org", "/"),
+];
+
+// Registries found in lockfiles (Cargo.lock, package-lock.json, yarn.lock, poetry.lock, go.sum)
+const REGISTRIES: [(&str, &str); 12] = [
+    ("github.com", "/rust-lang/crates.io-index"),
+    ("index.crates.io", "/"),
+    ("static.crates.io", "/"),
+    ("registry.npmjs.org", "/"),
+    ("registry.yarnpkg.com", "/"),
+    ("registry.npmmirror.com", "/"),
+    ("files.pythonhosted.org", "/"),
+    ("pypi.org", "/simple"),
+    ("proxy.golang.org", "/"),
+    ("sum.golang.org", "/"),
+    ("repo.maven.apache.org", "/"),
+    ("repo1.maven.org", "/"),
+];
+
+impl NoiseFilter {
+    pub fn matches(&self, url: &str) -> bool {
+        let parsed_url = match Url::parse(url) {
+            Ok(parsed_url) => parsed_url,
+            Err(_) => return false,
+        };
+        let host = match parsed_url.host() {
+            Some(host) => host,
+            None => return false,
+        };
+        match self {
+            NoiseFilter::Placeholder => match host {
+                Host::Domain(domain) => is_placeholder_domain(domain),
+                _ => false,
+            },
+            NoiseFilter::PrivateNetwork => is_private_host(&host),
+            NoiseFilter::XmlNamespace => is_xml_namespace(&parsed_url),
+            NoiseFilter::License => matches_any(&parsed_url, &LICENSES),
+            NoiseFilter::Registry => matches_any(&parsed_url, &REGISTRIES),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NoiseFilter::Placeholder => "placeholder",
+            NoiseFilter::PrivateNetwork => "private-network",
+            NoiseFilter::XmlNamespace => "xml-namespace",
+            NoiseFilter::License => "license",
+            NoiseFilter::Registry => "registry",
+        }
+    }
+
+    // First enabled filter matching the URL
+    pub fn find_matching(filters: &[NoiseFilter], url: &str) -> Option<NoiseFilter> {
+        filters.iter().find(|filter| filter.matches(url)).copied()
+    }
+}
+
+impl fmt::Display for NoiseFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for NoiseFilter {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<NoiseFilter, String> {
+        ALL_NOISE_FILTERS
+            .iter()
+            .find(|filter| filter.name() == name)
+            .copied()
+            .ok_or_else(|| format!("unknown noise filter '{}'", name))
+    }
+}
+
+fn is_placeholder_domain(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.');
+    let tld = domain.rsplit('.').next().unwrap_or(domain);
+    PLACEHOLDER_TLDS.contains(&tld)
+        || PLACEHOLDER_DOMAINS
+            .iter()
+            .any(|placeholder| is_same_or_subdomain(domain, placeholder))
+}
+
+fn is_private_host(host: &Host<&str>) -> bool {
+    match host {
+        Host::Domain(domain) => is_same_or_subdomain(domain, "localhost"),
+        Host::Ipv4(ip) => {
+            ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+        }
+        Host::Ipv6(ip) => {
+            let first_segment = ip.segments()[0];
+            ip.is_loopback()
+                || ip.is_unspecified()
+                || (first_segment & 0xfe00) == 0xfc00
+                || (first_segment & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+// w3.org namespaces are dated, like http://www.w3.org/2001/XMLSchema
+fn is_xml_namespace(url: &Url) -> bool {
+    let is_dated_w3_path = || {
+        url.path_segments()
+            .and_then(|mut segments| segments.next())
+            .map(|segment| segment.len() == 4 && segment.chars().all(|c| c.is_ascii_digit()))
+            .unwrap_or(false)
+    };
+    matches_any(url, &XML_NAMESPACES)
+        || (matches!(url.host_str(), Some("www.w3.org") | Some("w3.org")) && is_dated_w3_path())
+}
+
+fn matches_any(url: &Url, hosts_and_paths: &[(&str, &str)]) -> bool {
+    let host = url.host_str().unwrap_or_default();
+    hosts_and_paths
+        .iter()
+        .any(|(known_host, path)| host == *known_host && url.path().starts_with(path))
+}
+
+fn is_same_or_subdomain(domain: &str, parent: &str) -> bool {
+    domain == parent || domain.ends_with(&format!(".{}", parent))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::noise_filter::*;
+
+    fn assert_filtered(filter: NoiseFilter, urls: &[&str]) {
+        for url in urls {
+            assert!(filter.matches(url), "{} should match {}", filter, url);
+        }
+    }
+
+    fn assert_not_filtered(filter: NoiseFilter, urls: &[&str]) {
+        for url in urls {
+            assert!(!filter.matches(url), "{} should not match {}", filter, url);
+        }
+    }
+
+    #[test]
+    fn placeholder() {
+        assert_filtered(
+            NoiseFilter::Placeholder,
+            &[
+                "https://example.com",
+                "https://www.example.org/page",
+                "http://api.test/v1",
+                "https://site1.tld",
+            ],
+        );
+        assert_not_filtered(
+            NoiseFilter::Placeholder,
+            &["https://github.com", "https://example.company.com"],
+        );
+    }
+
+    #[test]
+    fn private_network() {
+        assert_filtered(
+            NoiseFilter::PrivateNetwork,
+            &[
+                "http://localhost:8080",
+                "http://127.0.0.1/api",
+                "http://192.168.1.10",
+                "http://10.0.0.1",
+                "http://172.16.0.1",
+                "http://[::1]:3000",
+                "http://0.0.0.0:8000",
+            ],
+        );
+        assert_not_filtered(
+            NoiseFilter::PrivateNetwork,
+            &["http://8.8.8.8", "https://localhost.company.com"],
+        );
+    }
+
+    #[test]
+    fn xml_namespace() {
+        assert_filtered(
+            NoiseFilter::XmlNamespace,
+            &[
+                "http://www.w3.org/2001/XMLSchema",
+                "http://www.w3.org/1999/xhtml",
+                "http://maven.apache.org/POM/4.0.0",
+                "http://schemas.xmlsoap.org/soap/envelope",
+            ],
+        );
+        assert_not_filtered(
+            NoiseFilter::XmlNamespace,
+            &[
+                "https://www.w3.org/TR/html52",
+                "https://maven.apache.org/guides",
+            ],
+        );
+    }
+
+    #[test]
+    fn license() {
+        assert_filtered(
+            NoiseFilter::License,
+            &[
+                "http://www.apache.org/licenses/LICENSE-2.0",
+                "https://spdx.org/licenses/MIT.html",
+                "https://www.gnu.org/licenses/gpl-3.0.html",
+            ],
+        );
+        assert_not_filtered(NoiseFilter::License, &["https://www.apache.org/foundation"]);
+    }
+
+    #[test]
+    fn registry() {
+        assert_filtered(
+            NoiseFilter::Registry,
+            &[
+                "https://github.com/rust-lang/crates.io-index",
+                "https://registry.npmjs.org/serde/-/serde-1.0.0.tgz",
+                "https://files.pythonhosted.org/packages/ab/cd/pkg.whl",
+            ],
+        );
+        assert_not_filtered(
+            NoiseFilter::Registry,
+            &[
+                "https://github.com/rlespinasse/wints",
+                "https://pypi.org/project/wints",
+            ],
+        );
+    }
+
+    #[test]
+    fn find_matching() {
+        assert_eq!(
+            Some(NoiseFilter::License),
+            NoiseFilter::find_matching(&ALL_NOISE_FILTERS, "https://spdx.org/licenses/MIT.html")
+        );
+        assert_eq!(
+            None,
+            NoiseFilter::find_matching(&[NoiseFilter::Registry], "https://example.com")
+        );
+    }
+
+    #[test]
+    fn names() {
+        for filter in ALL_NOISE_FILTERS {
+            assert_eq!(Ok(filter), filter.to_string().parse::<NoiseFilter>());
+        }
+        assert!("unknown".parse::<NoiseFilter>().is_err());
+    }
+}
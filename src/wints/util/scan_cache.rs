@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fs::{File, Metadata};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::util::file_store;
+use crate::util::url_scanner::ScannedUrl;
+
+const CACHE_VERSION: u8 = 1;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScanCache {
+    version: u8,
+    files: BTreeMap<PathBuf, CacheEntry>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub mtime: u64,
+    pub size: u64,
+    pub hash: String,
+    // URLs only extracted from the comments
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub comments_only: bool,
+    // Site root used to resolve the relative links of an HTML file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub site_root: Option<String>,
+    pub urls: Vec<ScannedUrl>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ScanCacheStats {
+    pub reused_files: usize,
+    pub scanned_files: usize,
+}
+
+impl ScanCache {
+    // A missing or unreadable cache is just an empty one
+    pub fn load(path: &Path) -> ScanCache {
+        File::open(path)
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, ScanCache>(file).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .unwrap_or_else(ScanCache::empty_cache)
+    }
+
+    pub fn empty_cache() -> ScanCache {
+        ScanCache {
+            version: CACHE_VERSION,
+            files: Default::default(),
+        }
+    }
+
+    pub fn store(&self, path: &Path) -> Result<()> {
+        file_store::write_atomically(path, serde_json::to_string(self)?.as_bytes())
+    }
+
+    pub fn get(&self, key: &Path) -> Option<&CacheEntry> {
+        self.files.get(key)
+    }
+
+    // Entries under the scanned root are replaced by the visited ones,
+    // so the files deleted (or ignored) since the last scan are dropped
+    pub fn update(&mut self, scan_root: &Path, visited: Vec<(PathBuf, CacheEntry)>) {
+        self.files
+            .retain(|key, _| !key.starts_with(scan_root) && key.exists());
+        self.files.extend(visited);
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+impl CacheEntry {
+    pub fn is_unchanged(&self, metadata: &Metadata) -> bool {
+        self.mtime == mtime_of(metadata) && self.size == metadata.len()
+    }
+}
+
+pub fn mtime_of(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+// FNV-1a, stable across Rust versions unlike the std hasher
+pub fn content_hash(content: &[u8]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in content {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write;
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+
+    use crate::util::scan_cache::*;
+
+    fn some_entry() -> CacheEntry {
+        CacheEntry {
+            mtime: 1,
+            size: 2,
+            hash: content_hash(b"content"),
+            comments_only: false,
+            site_root: None,
+            urls: vec![],
+        }
+    }
+
+    #[test]
+    fn stable_content_hash() {
+        assert_eq!("cbf29ce484222325", content_hash(b""));
+        assert_eq!("af63dc4c8601ec8c", content_hash(b"a"));
+    }
+
+    #[test]
+    fn load_missing_cache() {
+        let dir = tempdir().unwrap();
+        let cache = ScanCache::load(&dir.path().join("scan.json"));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn load_invalid_cache() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("scan.json");
+        let mut file = File::create(&path)?;
+        writeln!(file, "not a cache")?;
+
+        assert!(ScanCache::load(&path).is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn store_and_load() -> Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("cache").join("scan.json");
+        let mut cache = ScanCache::empty_cache();
+        cache.update(dir.path(), vec![(dir.path().join("file"), some_entry())]);
+        cache.store(&path)?;
+
+        assert_eq!(cache, ScanCache::load(&path));
+        Ok(())
+    }
+
+    #[test]
+    fn update_drops_unvisited_files_under_root() -> Result<()> {
+        let dir = tempdir()?;
+        let outside = dir.path().join("outside.txt");
+        File::create(&outside)?;
+        let root = dir.path().join("root");
+
+        let mut cache = ScanCache::empty_cache();
+        cache.update(
+            dir.path(),
+            vec![
+                (outside.clone(), some_entry()),
+                (root.join("deleted.txt"), some_entry()),
+                (dir.path().join("missing.txt"), some_entry()),
+            ],
+        );
+        cache.update(&root, vec![(root.join("kept.txt"), some_entry())]);
+
+        assert_eq!(2, cache.len());
+        assert!(cache.get(&outside).is_some());
+        assert!(cache.get(&root.join("kept.txt")).is_some());
+        assert!(cache.get(&root.join("deleted.txt")).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Result};
+use ignore::types::{Types, TypesBuilder};
+use ignore::WalkBuilder;
+
+// Which files are walked during a scan
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScanSelection {
+    // File types known by ripgrep, like 'md' or 'lock'
+    pub types: Vec<String>,
+    pub types_not: Vec<String>,
+    pub max_depth: Option<usize>,
+    // In bytes
+    pub max_filesize: Option<u64>,
+    pub hidden: bool,
+    pub follow_symlinks: bool,
+    pub gitignore: bool,
+}
+
+impl Default for ScanSelection {
+    fn default() -> ScanSelection {
+        ScanSelection {
+            types: vec![],
+            types_not: vec![],
+            max_depth: None,
+            max_filesize: None,
+            hidden: false,
+            follow_symlinks: false,
+            gitignore: true,
+        }
+    }
+}
+
+impl ScanSelection {
+    // Fail on unknown file types before walking anything
+    pub fn validate(&self) -> Result<()> {
+        self.file_types().map(|_| ())
+    }
+
+    pub fn apply(&self, walker: &mut WalkBuilder) {
+        if let Ok(Some(types)) = self.file_types() {
+            walker.types(types);
+        }
+        walker
+            .max_depth(self.max_depth)
+            .max_filesize(self.max_filesize)
+            .hidden(!self.hidden)
+            .follow_links(self.follow_symlinks)
+            .git_ignore(self.gitignore)
+            .git_global(self.gitignore)
+            .git_exclude(self.gitignore);
+        // Files whitelisted by a type are not checked for being hidden by the walker
+        if !self.hidden {
+            walker.filter_entry(|entry| {
+                entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.')
+            });
+        }
+    }
+
+    fn file_types(&self) -> Result<Option<Types>> {
+        if self.types.is_empty() && self.types_not.is_empty() {
+            return Ok(None);
+        }
+        let mut builder = TypesBuilder::new();
+        builder.add_defaults();
+        self.types.iter().for_each(|name| {
+            builder.select(name);
+        });
+        self.types_not.iter().for_each(|name| {
+            builder.negate(name);
+        });
+        builder
+            .build()
+            .map(Some)
+            .map_err(|err| anyhow!("invalid file type: {}", err))
+    }
+}
+
+// Sizes like '512', '100K', '1M' or '2G'
+pub fn parse_filesize(size: &str) -> Result<u64> {
+    let size = size.trim();
+    let (digits, multiplier) = match size.chars().last().map(|c| c.to_ascii_uppercase()) {
+        Some('K') => (&size[..size.len() - 1], 1 << 10),
+        Some('M') => (&size[..size.len() - 1], 1 << 20),
+        Some('G') => (&size[..size.len() - 1], 1 << 30),
+        _ => (size, 1),
+    };
+    digits
+        .parse::<u64>()
+        .map(|value| value * multiplier)
+        .map_err(|_| {
+            anyhow!(
+                "invalid file size '{}', expected like 512, 100K or 1M",
+                size
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::scan_selection::*;
+
+    #[test]
+    fn file_sizes() {
+        assert_eq!(512, parse_filesize("512").unwrap());
+        assert_eq!(100 * 1024, parse_filesize("100K").unwrap());
+        assert_eq!(1024 * 1024, parse_filesize("1m").unwrap());
+        assert_eq!(2 * 1024 * 1024 * 1024, parse_filesize("2G").unwrap());
+        assert!(parse_filesize("big").is_err());
+        assert!(parse_filesize("").is_err());
+    }
+
+    #[test]
+    fn file_types() {
+        let selection = ScanSelection {
+            types: vec!["md".to_string()],
+            types_not: vec!["lock".to_string()],
+            ..ScanSelection::default()
+        };
+        assert!(selection.validate().is_ok());
+
+        let selection = ScanSelection {
+            types: vec!["not-a-type".to_string()],
+            ..ScanSelection::default()
+        };
+        assert!(selection.validate().is_err());
+    }
+}
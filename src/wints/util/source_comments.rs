@@ -0,0 +1,253 @@
+use std::path::Path;
+
+// How comments, docstrings and string literals are written in a language
+pub struct CommentSyntax {
+    line: &'static [&'static str],
+    block: &'static [(&'static str, &'static str)],
+    // Longest delimiters first, a string is never a comment
+    strings: &'static [&'static str],
+    // String delimiters which are also used for documentation
+    docstrings: &'static [&'static str],
+}
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    strings: &["\"", "'"],
+    docstrings: &[],
+};
+
+// ' is also used by lifetimes
+const RUST: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    strings: &["\""],
+    docstrings: &[],
+};
+
+const JAVASCRIPT: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    strings: &["\"", "'", "`"],
+    docstrings: &[],
+};
+
+const PYTHON: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    strings: &["\"\"\"", "'''", "\"", "'"],
+    docstrings: &["\"\"\"", "'''"],
+};
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    strings: &["\"", "'"],
+    docstrings: &[],
+};
+
+const RUBY: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[("=begin", "=end")],
+    strings: &["\"", "'"],
+    docstrings: &[],
+};
+
+const PHP: CommentSyntax = CommentSyntax {
+    line: &["//", "#"],
+    block: &[("/*", "*/")],
+    strings: &["\"", "'"],
+    docstrings: &[],
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("--[[", "]]")],
+    strings: &["\"", "'"],
+    docstrings: &[],
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("/*", "*/")],
+    strings: &["'"],
+    docstrings: &[],
+};
+
+// Extensions of the source files where comments can be told apart from code
+pub const COMMENT_EXTENSIONS: [&str; 32] = [
+    "rs", "c", "h", "cc", "cpp", "hpp", "cs", "java", "kt", "kts", "scala", "go", "swift", "dart",
+    "groovy", "gradle", "js", "jsx", "mjs", "cjs", "ts", "tsx", "py", "sh", "bash", "zsh", "pl",
+    "r", "rb", "php", "lua", "sql",
+];
+
+pub fn comment_syntax(path: &Path) -> Option<&'static CommentSyntax> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    let syntax = match extension.as_str() {
+        "rs" => &RUST,
+        "c" | "h" | "cc" | "cpp" | "hpp" | "cs" | "java" | "kt" | "kts" | "scala" | "go"
+        | "swift" | "dart" | "groovy" | "gradle" => &C_LIKE,
+        "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx" => &JAVASCRIPT,
+        "py" => &PYTHON,
+        "sh" | "bash" | "zsh" | "pl" | "r" => &HASH,
+        "rb" => &RUBY,
+        "php" => &PHP,
+        "lua" => &LUA,
+        "sql" => &SQL,
+        _ => return None,
+    };
+    Some(syntax)
+}
+
+// Blank everything but the comments and docstrings, lines and columns are kept
+pub fn mask_code(content: &[u8], syntax: &CommentSyntax) -> Vec<u8> {
+    let mut kept = vec![false; content.len()];
+    let mut index = 0;
+    while index < content.len() {
+        let rest = &content[index..];
+        let end = if let Some(line) = find_prefix(rest, syntax.line) {
+            let end = index + find(rest, b"\n").unwrap_or(rest.len());
+            mark(&mut kept, index + line.len(), end);
+            end
+        } else if let Some((open, close)) = syntax
+            .block
+            .iter()
+            .find(|(open, _)| rest.starts_with(open.as_bytes()))
+        {
+            let (comment_end, end) = match find(&rest[open.len()..], close.as_bytes()) {
+                Some(position) => {
+                    let comment_end = index + open.len() + position;
+                    (comment_end, comment_end + close.len())
+                }
+                None => (content.len(), content.len()),
+            };
+            mark(&mut kept, index + open.len(), comment_end);
+            end
+        } else if let Some(delimiter) = find_prefix(rest, syntax.strings) {
+            let end = index + string_length(rest, delimiter);
+            if syntax.docstrings.contains(&delimiter) {
+                let docstring_end = match content[..end].ends_with(delimiter.as_bytes()) {
+                    true => end - delimiter.len(),
+                    false => end,
+                };
+                mark(&mut kept, index + delimiter.len(), docstring_end);
+            }
+            end
+        } else {
+            index + 1
+        };
+        index = end.max(index + 1);
+    }
+
+    content
+        .iter()
+        .zip(kept)
+        .map(|(byte, kept)| match kept || *byte == b'\n' {
+            true => *byte,
+            false => b' ',
+        })
+        .collect()
+}
+
+// Length of a string literal with its delimiters, single line strings stop at the end of the line
+fn string_length(content: &[u8], delimiter: &str) -> usize {
+    let single_line = delimiter.len() == 1 && delimiter != "`";
+    let mut index = delimiter.len();
+    while index < content.len() {
+        if content[index] == b'\\' {
+            index += 2;
+            continue;
+        }
+        if single_line && content[index] == b'\n' {
+            return index;
+        }
+        if content[index..].starts_with(delimiter.as_bytes()) {
+            return index + delimiter.len();
+        }
+        index += 1;
+    }
+    content.len()
+}
+
+fn find_prefix(content: &[u8], prefixes: &[&'static str]) -> Option<&'static str> {
+    prefixes
+        .iter()
+        .find(|prefix| content.starts_with(prefix.as_bytes()))
+        .copied()
+}
+
+fn find(content: &[u8], needle: &[u8]) -> Option<usize> {
+    content
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn mark(kept: &mut [bool], start: usize, end: usize) {
+    let end = end.min(kept.len());
+    if start < end {
+        kept[start..end].iter_mut().for_each(|kept| *kept = true);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::util::source_comments::*;
+
+    fn masked(file_name: &str, content: &str) -> String {
+        let syntax = comment_syntax(Path::new(file_name)).unwrap();
+        String::from_utf8(mask_code(content.as_bytes(), syntax)).unwrap()
+    }
+
+    #[test]
+    fn keep_line_and_block_comments() {
+        let content = r#"
+// See https://doc.tld
+let api = "https://api.tld"; /* https://block.tld */
+"#;
+        let result = masked("main.rs", content);
+        assert!(result.contains("https://doc.tld"));
+        assert!(result.contains("https://block.tld   "));
+        assert!(!result.contains("https://api.tld"));
+        assert_eq!(content.len(), result.len());
+        assert_eq!(content.lines().count(), result.lines().count());
+    }
+
+    #[test]
+    fn ignore_comment_markers_inside_strings() {
+        let result = masked("app.js", "const url = 'http://localhost:8080'; // ok\n");
+        assert!(!result.contains("localhost"));
+        assert!(result.contains(" ok"));
+    }
+
+    #[test]
+    fn keep_python_docstrings() {
+        let content = r#"
+def fetch():
+    """Fetch, see https://doc.tld"""
+    return get("https://api.tld")  # https://comment.tld
+"#;
+        let result = masked("api.py", content);
+        assert!(result.contains("https://doc.tld   "));
+        assert!(result.contains("https://comment.tld"));
+        assert!(!result.contains("https://api.tld"));
+    }
+
+    #[test]
+    fn keep_columns() {
+        let content = "x = 1 # https://doc.tld\n";
+        let result = masked("run.sh", content);
+        assert_eq!(content.find("https"), result.find("https"));
+    }
+
+    #[test]
+    fn unknown_languages() {
+        assert!(comment_syntax(Path::new("README.md")).is_none());
+        assert!(comment_syntax(Path::new("notes.txt")).is_none());
+        assert!(comment_syntax(Path::new("Makefile")).is_none());
+        assert!(COMMENT_EXTENSIONS
+            .iter()
+            .all(|extension| comment_syntax(Path::new(&format!("file.{}", extension))).is_some()));
+    }
+}
@@ -0,0 +1,208 @@
+use url::{form_urlencoded, Position, Url};
+
+// Query parameters only used to track the visitors, a trailing '*' match any suffix
+pub const DEFAULT_TRACKING_PARAMS: [&str; 7] = [
+    "utm_*", "fbclid", "gclid", "msclkid", "mc_cid", "mc_eid", "yclid",
+];
+
+const TRAILING_PUNCTUATION: [char; 9] = ['.', ',', ';', ':', '!', '?', '\'', '"', '*'];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UrlNormaliser {
+    tracking_params: Vec<String>,
+}
+
+impl Default for UrlNormaliser {
+    fn default() -> UrlNormaliser {
+        UrlNormaliser::new(
+            DEFAULT_TRACKING_PARAMS
+                .iter()
+                .map(|param| param.to_string())
+                .collect(),
+        )
+    }
+}
+
+impl UrlNormaliser {
+    pub fn new(tracking_params: Vec<String>) -> UrlNormaliser {
+        UrlNormaliser { tracking_params }
+    }
+
+    // Canonical form used to compare URLs, an unparsable URL is only trimmed
+    pub fn canonicalise(&self, url: &str) -> String {
+        let url = strip_trailing_punctuation(url.trim());
+        let mut parsed_url = match Url::parse(url) {
+            Ok(parsed_url) => parsed_url,
+            Err(_) => return url.to_string(),
+        };
+        if parsed_url.cannot_be_a_base() {
+            return parsed_url.to_string();
+        }
+
+        self.strip_tracking_params(&mut parsed_url);
+
+        // The root path stays '/', other paths lose their trailing slashes
+        let path = match parsed_url.path().trim_end_matches('/') {
+            "" => "/",
+            path => path,
+        };
+        format!(
+            "{}{}{}",
+            &parsed_url[..Position::BeforePath],
+            path,
+            &parsed_url[Position::AfterPath..]
+        )
+    }
+
+    pub fn same_url(&self, first: &str, second: &str) -> bool {
+        self.canonicalise(first) == self.canonicalise(second)
+    }
+
+    // The kept pairs keep their original encoding, only the tracking ones are removed
+    fn strip_tracking_params(&self, url: &mut Url) {
+        let query = match url.query() {
+            Some(query) => query.to_string(),
+            None => return,
+        };
+        let pairs: Vec<&str> = query.split('&').collect();
+        let kept_pairs: Vec<&str> = pairs
+            .iter()
+            .filter(|pair| !self.is_tracking_param(&query_key(pair)))
+            .copied()
+            .collect();
+        if kept_pairs.len() == pairs.len() {
+            return;
+        }
+        match kept_pairs.is_empty() {
+            true => url.set_query(None),
+            false => url.set_query(Some(&kept_pairs.join("&"))),
+        }
+    }
+
+    fn is_tracking_param(&self, key: &str) -> bool {
+        self.tracking_params
+            .iter()
+            .any(|param| match param.strip_suffix('*') {
+                Some(prefix) => key.starts_with(prefix),
+                None => key == param,
+            })
+    }
+}
+
+// Decoded key of a raw 'key=value' query pair
+fn query_key(pair: &str) -> String {
+    form_urlencoded::parse(pair.as_bytes())
+        .next()
+        .map(|(key, _)| key.to_string())
+        .unwrap_or_default()
+}
+
+// Keep closing brackets only when they are balanced inside the URL
+fn strip_trailing_punctuation(url: &str) -> &str {
+    let mut url = url;
+    loop {
+        let last = match url.chars().last() {
+            Some(last) => last,
+            None => return url,
+        };
+        let unbalanced = match last {
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            ']' => url.matches('[').count() < url.matches(']').count(),
+            '>' => !url.contains('<'),
+            _ => TRAILING_PUNCTUATION.contains(&last),
+        };
+        match unbalanced {
+            true => url = &url[..url.len() - last.len_utf8()],
+            false => return url,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::util::url_normaliser::UrlNormaliser;
+
+    fn assert_canonical(expected: &str, url: &str) {
+        assert_eq!(expected, UrlNormaliser::default().canonicalise(url));
+    }
+
+    #[test]
+    fn lowercase_scheme_and_host() {
+        assert_canonical("https://site.tld/Path", "HTTPS://Site.TLD/Path");
+    }
+
+    #[test]
+    fn normalise_trailing_slashes() {
+        assert_canonical("https://site.tld/", "https://site.tld/");
+        assert_canonical("https://site.tld/", "https://site.tld");
+        assert_canonical("https://site.tld/docs", "https://site.tld/docs/");
+        assert_canonical("https://site.tld/docs", "https://site.tld/docs//");
+        assert_canonical("https://site.tld/?q=1", "https://site.tld/?q=1");
+    }
+
+    #[test]
+    fn remove_default_ports() {
+        assert_canonical("https://site.tld/", "https://site.tld:443/");
+        assert_canonical("http://site.tld/", "http://site.tld:80");
+        assert_canonical("http://site.tld:8080/", "http://site.tld:8080/");
+    }
+
+    #[test]
+    fn strip_tracking_params() {
+        assert_canonical(
+            "https://site.tld/page",
+            "https://site.tld/page?utm_source=mail&utm_medium=email",
+        );
+        assert_canonical(
+            "https://site.tld/page?id=3",
+            "https://site.tld/page?utm_source=mail&id=3&fbclid=abc",
+        );
+        assert_canonical(
+            "https://site.tld/page?id=3&ref=a%20b",
+            "https://site.tld/page?id=3&ref=a%20b",
+        );
+    }
+
+    #[test]
+    fn keep_the_encoding_of_the_kept_params() {
+        assert_canonical(
+            "https://site.tld/page?q=a%20b&ref=x+y",
+            "https://site.tld/page?q=a%20b&utm_source=mail&ref=x+y",
+        );
+        assert_canonical(
+            "https://site.tld/page?q=%C3%A9t%C3%A9",
+            "https://site.tld/page?utm%5Fsource=mail&q=%C3%A9t%C3%A9",
+        );
+    }
+
+    #[test]
+    fn strip_configured_tracking_params() {
+        let normaliser = UrlNormaliser::new(vec!["ref".to_string()]);
+        assert_eq!(
+            "https://site.tld/page?utm_source=mail",
+            normaliser.canonicalise("https://site.tld/page?utm_source=mail&ref=home")
+        );
+    }
+
+    #[test]
+    fn remove_trailing_punctuation() {
+        assert_canonical("https://site.tld/", "https://site.tld.");
+        assert_canonical("https://site.tld/page", "https://site.tld/page),");
+        assert_canonical(
+            "https://site.tld/wiki/Rust_(language)",
+            "https://site.tld/wiki/Rust_(language)",
+        );
+    }
+
+    #[test]
+    fn keep_unparsable_urls() {
+        assert_canonical("not an url", " not an url ");
+    }
+
+    #[test]
+    fn same_url() {
+        let normaliser = UrlNormaliser::default();
+        assert!(normaliser.same_url("https://x.tld", "HTTPS://X.tld/?utm_campaign=x"));
+        assert!(!normaliser.same_url("https://x.tld", "https://y.tld"));
+    }
+}
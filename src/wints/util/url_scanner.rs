@@ -1,72 +1,710 @@
-use std::path::PathBuf;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::thread;
 
 use anyhow::Result;
-use grep::regex::RegexMatcher;
-use grep::searcher::sinks::UTF8;
-use grep::searcher::Searcher;
-use ignore::{Walk, WalkBuilder};
+use grep::regex::{RegexMatcher, RegexMatcherBuilder};
+use grep::searcher::{BinaryDetection, Searcher, SearcherBuilder, Sink, SinkMatch};
+use ignore::overrides::OverrideBuilder;
+use ignore::{DirEntry, WalkBuilder, WalkParallel, WalkState};
 use linkify::{LinkFinder, LinkKind};
+use serde::{Deserialize, Serialize};
+use url::Url;
 
-const URL_PATTERN: &str = r#"(http://|https://)"#;
+use crate::util::document_extractor::document_extractor;
+use crate::util::html_links::{decode_entities, HtmlLinkFinder, HTML_EXTENSIONS};
+use crate::util::scan_cache::{content_hash, mtime_of, CacheEntry, ScanCache, ScanCacheStats};
+use crate::util::scan_selection::ScanSelection;
+use crate::util::source_comments;
+use crate::util::source_comments::CommentSyntax;
 
+// Lines with an URL, or lines which may be a Markdown/AsciiDoc heading or code block delimiter
+const LINE_PATTERN: &str =
+    r#"(http://|https://)|^\s*(#{1,6}|={1,6})\s|^\s*(```|~~~|----|\.\.\.\.|\+\+\+\+)"#;
+
+// Files where headings are meaningful to give a context to the URLs
+const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+const ASCIIDOC_EXTENSIONS: [&str; 3] = ["adoc", "asciidoc", "asc"];
+
+#[derive(Clone)]
 pub struct UrlScannerOptions {
     pub ignore_files: Vec<PathBuf>,
+    // Gitignore-like globs, relative to the ignore root
+    pub ignore_globs: Vec<String>,
     pub scan_path: PathBuf,
+    // Folder holding the local configuration, whatever the scanned path
+    pub ignore_root: PathBuf,
+    // 0 let the walker choose the number of threads
+    pub threads: usize,
+    pub selection: ScanSelection,
+    // Extensions of the source files where only comments and docstrings are scanned
+    pub comments_only: Vec<String>,
+    // URL of the scanned path, to resolve the relative links of HTML files
+    pub site_root: Option<Url>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ScannedUrl {
+    pub url: String,
+    // Cached URLs are stored by path
+    #[serde(skip)]
+    pub path: PathBuf,
+    pub line: u64,
+    pub column: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heading: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_text: Option<String>,
+}
+
+impl ScannedUrl {
+    fn new(url: String) -> ScannedUrl {
+        ScannedUrl {
+            url,
+            path: PathBuf::new(),
+            line: 0,
+            column: 0,
+            heading: None,
+            link_text: None,
+        }
+    }
+
+    // Location of the URL as 'path:line:column', relative to the project root when under it
+    pub fn source(&self, project_root: &Path) -> String {
+        let path = self
+            .path
+            .strip_prefix(project_root)
+            .or_else(|_| self.path.strip_prefix("."))
+            .unwrap_or(&self.path);
+        format!("{}:{}:{}", path.display(), self.line, self.column)
+    }
+
+    // Path of a location built by source()
+    pub fn path_of_source(source: &str) -> PathBuf {
+        PathBuf::from(source.rsplitn(3, ':').last().unwrap_or(source))
+    }
+
+    // Build a context from the nearest heading and the link text, like 'deployment runbook'
+    pub fn suggested_context(&self) -> Option<String> {
+        let mut terms: Vec<String> = vec![];
+        for text in [&self.heading, &self.link_text].into_iter().flatten() {
+            if text.contains("://") {
+                continue;
+            }
+            for term in text.split_whitespace().map(|term| term.to_lowercase()) {
+                if !terms.contains(&term) {
+                    terms.push(term);
+                }
+            }
+        }
+        match terms.is_empty() {
+            true => None,
+            false => Some(terms.join(" ")),
+        }
+    }
 }
 
 pub fn scan_urls(options: UrlScannerOptions) -> Vec<String> {
-    let files: Vec<PathBuf> = build_walk(options)
-        .filter_map(|r| r.ok())
-        .filter(|d| d.path().is_file())
-        .map(|d| d.into_path())
-        .collect();
+    scan(options, None, |scanned_url| scanned_url.url).results
+}
 
-    let mut raw_urls: Vec<String> = files
+pub fn scan_urls_with_context(options: UrlScannerOptions) -> Vec<ScannedUrl> {
+    scan(options, None, |scanned_url| scanned_url).results
+}
+
+// Files which would be scanned, sorted
+pub fn list_files(options: UrlScannerOptions) -> Vec<PathBuf> {
+    let (sender, receiver) = mpsc::channel::<PathBuf>();
+    build_walk(options).run(|| {
+        let sender = sender.clone();
+        Box::new(move |entry| {
+            if let Ok(entry) = entry {
+                if is_scanned_file(&entry) {
+                    let _ = sender.send(entry.into_path());
+                }
+            }
+            WalkState::Continue
+        })
+    });
+    drop(sender);
+    let mut files: Vec<PathBuf> = receiver.into_iter().collect();
+    files.sort();
+    files
+}
+
+// URLs of lines which are not read from a file, like commit messages
+pub fn scan_lines(lines: Vec<(PathBuf, u64, String)>) -> Vec<ScannedUrl> {
+    let scanner = UrlScanner::new();
+    lines
         .into_iter()
-        .flat_map(|file| extract_lines_with_url(file).unwrap_or_default())
-        .flat_map(extract_urls)
-        .collect();
+        .flat_map(|(path, line_num, line)| {
+            scanner
+                .extract_urls_with_link_text(&line)
+                .into_iter()
+                .map(move |scanned_url| ScannedUrl {
+                    path: path.clone(),
+                    line: line_num,
+                    ..scanned_url
+                })
+        })
+        .collect()
+}
+
+// Only the files changed since the last scan are parsed, the cache is updated accordingly
+pub fn scan_urls_with_cache(
+    options: UrlScannerOptions,
+    cache: &mut ScanCache,
+) -> (Vec<ScannedUrl>, ScanCacheStats) {
+    let scan_path = options.scan_path.clone();
+    let scan_root = fs::canonicalize(&scan_path).unwrap_or_else(|_| scan_path.clone());
+    let cache_context = CacheContext {
+        cache,
+        scan_path,
+        scan_root: scan_root.clone(),
+    };
+    let outcome = scan(options, Some(&cache_context), |scanned_url| scanned_url);
+    cache.update(&scan_root, outcome.visited);
+    (outcome.results, outcome.stats)
+}
+
+struct CacheContext<'a> {
+    cache: &'a ScanCache,
+    scan_path: PathBuf,
+    scan_root: PathBuf,
+}
+
+impl CacheContext<'_> {
+    fn key(&self, path: &Path) -> PathBuf {
+        match path.strip_prefix(&self.scan_path) {
+            Ok(relative_path) => self.scan_root.join(relative_path),
+            Err(_) => path.to_path_buf(),
+        }
+    }
+}
+
+struct ScannedFile {
+    scanned_urls: Vec<ScannedUrl>,
+    cache_entry: Option<(PathBuf, CacheEntry)>,
+    reused: bool,
+}
+
+struct ScanOutcome<T> {
+    results: Vec<T>,
+    visited: Vec<(PathBuf, CacheEntry)>,
+    stats: ScanCacheStats,
+}
+
+// Walk the files in parallel, the results are deduplicated as soon as they are received
+fn scan<T, F>(
+    options: UrlScannerOptions,
+    cache_context: Option<&CacheContext>,
+    map: F,
+) -> ScanOutcome<T>
+where
+    T: Ord + Send,
+    F: Fn(ScannedUrl) -> T + Sync,
+{
+    let scanner = UrlScanner {
+        comments_only: options.comments_only.clone(),
+        site_root: options.site_root.clone(),
+        scan_path: options.scan_path.clone(),
+        ..UrlScanner::new()
+    };
+    let walker = build_walk(options);
+    let (sender, receiver) = mpsc::channel::<ScannedFile>();
+
+    thread::scope(|scope| {
+        let collector = scope.spawn(|| {
+            let mut results: BTreeSet<T> = BTreeSet::new();
+            let mut visited = vec![];
+            let mut stats = ScanCacheStats::default();
+            for scanned_file in receiver {
+                match scanned_file.reused {
+                    true => stats.reused_files += 1,
+                    false => stats.scanned_files += 1,
+                }
+                visited.extend(scanned_file.cache_entry);
+                results.extend(scanned_file.scanned_urls.into_iter().map(&map));
+            }
+            ScanOutcome {
+                results: results.into_iter().collect(),
+                visited,
+                stats,
+            }
+        });
+
+        walker.run(|| {
+            let scanner = &scanner;
+            let sender = sender.clone();
+            let mut searcher = SearcherBuilder::new()
+                .binary_detection(BinaryDetection::quit(b'\x00'))
+                .build();
+            Box::new(move |entry| {
+                let entry = match entry {
+                    Ok(entry) => entry,
+                    Err(_) => return WalkState::Continue,
+                };
+                if is_scanned_file(&entry) {
+                    let scanned_file =
+                        scanner.scan_file(&mut searcher, entry.path(), cache_context);
+                    if sender.send(scanned_file).is_err() {
+                        return WalkState::Quit;
+                    }
+                }
+                WalkState::Continue
+            })
+        });
+        drop(sender);
 
-    raw_urls.sort();
-    raw_urls.dedup();
-    raw_urls
+        collector.join().unwrap()
+    })
 }
 
-fn build_walk(options: UrlScannerOptions) -> Walk {
-    let mut walker = WalkBuilder::new(options.scan_path);
+fn build_walk(options: UrlScannerOptions) -> WalkParallel {
+    let mut walker = WalkBuilder::new(&options.scan_path);
     options.ignore_files.into_iter().for_each(|file| {
         walker.add_custom_ignore_filename(file);
     });
-    walker.build()
+    if !options.ignore_globs.is_empty() {
+        let mut overrides = OverrideBuilder::new(&options.ignore_root);
+        options.ignore_globs.iter().for_each(|glob| {
+            // Invalid globs are skipped, like invalid lines of an ignore file
+            let _ = overrides.add(&format!("!{}", glob));
+        });
+        if let Ok(overrides) = overrides.build() {
+            walker.overrides(overrides);
+        }
+    }
+    options.selection.apply(&mut walker);
+    walker.threads(options.threads);
+    walker.build_parallel()
+}
+
+// Matchers are compiled once and shared by all the walking threads
+struct UrlScanner {
+    line_matcher: RegexMatcher,
+    link_finder: LinkFinder,
+    html_link_finder: HtmlLinkFinder,
+    comments_only: Vec<String>,
+    site_root: Option<Url>,
+    scan_path: PathBuf,
 }
 
-fn extract_lines_with_url(path: PathBuf) -> Result<Vec<String>> {
-    let matcher = RegexMatcher::new(URL_PATTERN).unwrap();
+impl UrlScanner {
+    fn new() -> UrlScanner {
+        let line_matcher = RegexMatcherBuilder::new()
+            .multi_line(true)
+            .build(LINE_PATTERN)
+            .unwrap();
+        let mut link_finder = LinkFinder::new();
+        link_finder.kinds(&[LinkKind::Url]);
+
+        UrlScanner {
+            line_matcher,
+            link_finder,
+            html_link_finder: HtmlLinkFinder::new(),
+            comments_only: vec![],
+            site_root: None,
+            scan_path: PathBuf::new(),
+        }
+    }
+
+    fn scan_file(
+        &self,
+        searcher: &mut Searcher,
+        path: &Path,
+        cache_context: Option<&CacheContext>,
+    ) -> ScannedFile {
+        let scanned_file = |scanned_urls, cache_entry, reused| ScannedFile {
+            scanned_urls,
+            cache_entry,
+            reused,
+        };
+        let cache_context = match cache_context {
+            None => {
+                let scanned_urls = self
+                    .extract_urls_from_file(searcher, path, None)
+                    .unwrap_or_default();
+                return scanned_file(scanned_urls, None, false);
+            }
+            Some(cache_context) => cache_context,
+        };
+
+        let key = cache_context.key(path);
+        let cached_entry = cache_context.cache.get(&key);
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(_) => return scanned_file(vec![], None, false),
+        };
+        let comments_only = self.comment_syntax(path).is_some();
+        let site_root = match is_html_file(path) {
+            true => self.site_root.as_ref().map(Url::to_string),
+            false => None,
+        };
+        let cached_entry = cached_entry
+            .filter(|entry| entry.comments_only == comments_only && entry.site_root == site_root);
+        if let Some(entry) = cached_entry.filter(|entry| entry.is_unchanged(&metadata)) {
+            let scanned_urls = with_path(entry.urls.clone(), path);
+            return scanned_file(scanned_urls, Some((key, entry.clone())), true);
+        }
+
+        let content = match fs::read(path) {
+            Ok(content) => content,
+            Err(_) => return scanned_file(vec![], None, false),
+        };
+        let hash = content_hash(&content);
+        let (scanned_urls, reused) = match cached_entry.filter(|entry| entry.hash == hash) {
+            Some(entry) => (with_path(entry.urls.clone(), path), true),
+            None => (
+                self.extract_urls_from_file(searcher, path, Some(&content))
+                    .unwrap_or_default(),
+                false,
+            ),
+        };
+        let entry = CacheEntry {
+            mtime: mtime_of(&metadata),
+            size: metadata.len(),
+            hash,
+            comments_only,
+            site_root,
+            urls: scanned_urls.clone(),
+        };
+        scanned_file(scanned_urls, Some((key, entry)), reused)
+    }
+
+    fn comment_syntax(&self, path: &Path) -> Option<&'static CommentSyntax> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match self.comments_only.contains(&extension) {
+            true => source_comments::comment_syntax(path),
+            false => None,
+        }
+    }
+
+    // Relative links of a page are resolved against its URL below the site root
+    fn page_url(&self, path: &Path) -> Option<Url> {
+        let site_root = self.site_root.as_ref()?;
+        let relative_path = path.strip_prefix(&self.scan_path).unwrap_or(path);
+        let relative_path = relative_path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+        site_root.join(&relative_path).ok()
+    }
+
+    fn extract_urls_from_file(
+        &self,
+        searcher: &mut Searcher,
+        path: &Path,
+        content: Option<&[u8]>,
+    ) -> Result<Vec<ScannedUrl>> {
+        if let Some(extractor) = document_extractor(path) {
+            let content = match content {
+                Some(content) => content.to_vec(),
+                None => fs::read(path)?,
+            };
+            // Line numbers are the ones of the extracted text
+            let text = extractor.extract(&content)?;
+            return self.extract_urls_from_content(searcher, path, Some(text.as_bytes()));
+        }
+        if is_html_file(path) {
+            let content = match content {
+                Some(content) => content.to_vec(),
+                None => fs::read(path)?,
+            };
+            if content.contains(&0) {
+                return Ok(vec![]);
+            }
+            return self.extract_urls_from_html(searcher, path, &content);
+        }
+        if let Some(syntax) = self.comment_syntax(path) {
+            let content = match content {
+                Some(content) => content.to_vec(),
+                None => fs::read(path)?,
+            };
+            // Binary files are detected before the code is blanked
+            if content.contains(&0) {
+                return Ok(vec![]);
+            }
+            let comments = source_comments::mask_code(&content, syntax);
+            return self.extract_urls_from_content(searcher, path, Some(&comments));
+        }
+        self.extract_urls_from_content(searcher, path, content)
+    }
+
+    // Links of the tags, then the URLs written in the text
+    fn extract_urls_from_html(
+        &self,
+        searcher: &mut Searcher,
+        path: &Path,
+        content: &[u8],
+    ) -> Result<Vec<ScannedUrl>> {
+        let content = String::from_utf8_lossy(content);
+        let page_url = self.page_url(path);
+        let mut scanned_urls: Vec<ScannedUrl> = self
+            .html_link_finder
+            .links(&content, page_url.as_ref())
+            .into_iter()
+            .map(|link| {
+                let (line, column) = line_and_column(&content, link.offset);
+                ScannedUrl {
+                    path: path.to_path_buf(),
+                    line,
+                    column,
+                    link_text: link.link_text,
+                    ..ScannedUrl::new(link.url)
+                }
+            })
+            .collect();
+
+        let text = self.html_link_finder.mask_tags(&content);
+        let text_urls = self.extract_urls_from_content(searcher, path, Some(text.as_bytes()))?;
+        scanned_urls.extend(text_urls.into_iter().map(|scanned_url| ScannedUrl {
+            url: decode_entities(&scanned_url.url),
+            ..scanned_url
+        }));
+        Ok(scanned_urls)
+    }
+
+    fn extract_urls_from_content(
+        &self,
+        searcher: &mut Searcher,
+        path: &Path,
+        content: Option<&[u8]>,
+    ) -> Result<Vec<ScannedUrl>> {
+        let syntax = prose_syntax(path);
+        let mut heading: Option<String> = None;
+        // Lines of a code block are never headings, like '# comment' in a shell snippet
+        let mut code_block: Option<CodeFence> = None;
 
-    let mut matches = vec![];
-    Searcher::new().search_path(
-        &matcher,
-        &path,
-        UTF8(|_line_num, line| {
-            matches.push(line.trim().to_string());
-            Ok(true)
-        }),
-    )?;
+        let mut scanned_urls = vec![];
+        for (line_num, line) in self.extract_lines_with_url(searcher, path, content)? {
+            if let Some(syntax) = syntax {
+                match (code_fence(syntax, line.trim()), code_block) {
+                    (Some(fence), None) => code_block = Some(fence),
+                    (Some(fence), Some(opening)) if fence.closes(&opening) => code_block = None,
+                    (_, None) => {
+                        if let Some(new_heading) = extract_heading(syntax, line.trim()) {
+                            heading = Some(new_heading);
+                            continue;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            scanned_urls.extend(self.extract_urls_with_link_text(&line).into_iter().map(
+                |scanned_url| ScannedUrl {
+                    path: path.to_path_buf(),
+                    line: line_num,
+                    heading: heading.clone(),
+                    ..scanned_url
+                },
+            ));
+        }
+
+        Ok(scanned_urls)
+    }
+
+    fn extract_lines_with_url(
+        &self,
+        searcher: &mut Searcher,
+        path: &Path,
+        content: Option<&[u8]>,
+    ) -> Result<Vec<(u64, String)>> {
+        let mut collector = LineCollector::default();
+        match content {
+            Some(content) => searcher.search_slice(&self.line_matcher, content, &mut collector)?,
+            None => searcher.search_path(&self.line_matcher, path, &mut collector)?,
+        }
+
+        match collector.binary {
+            true => Ok(vec![]),
+            false => Ok(collector.lines),
+        }
+    }
+
+    fn extract_urls_with_link_text(&self, line: &str) -> Vec<ScannedUrl> {
+        self.link_finder
+            .links(line)
+            .map(|link| {
+                let url = url_cleaner(link.as_str().to_string());
+                let link_text = markdown_link_text(&line[..link.start()])
+                    .or_else(|| asciidoc_link_text(&line[link.start() + url.len()..]));
+                ScannedUrl {
+                    column: line[..link.start()].chars().count() + 1,
+                    link_text,
+                    ..ScannedUrl::new(url)
+                }
+            })
+            .collect()
+    }
+}
 
-    Ok(matches)
+// Keep lines which are not valid UTF-8, but drop the whole file when it is binary
+#[derive(Default)]
+struct LineCollector {
+    lines: Vec<(u64, String)>,
+    binary: bool,
 }
 
-fn extract_urls(line: String) -> Vec<String> {
-    let mut finder = LinkFinder::new();
-    finder.kinds(&[LinkKind::Url]);
+impl Sink for LineCollector {
+    type Error = io::Error;
 
-    finder
-        .links(line.as_str())
-        .map(|url| url.as_str().to_string())
-        .map(url_cleaner)
+    fn matched(&mut self, _searcher: &Searcher, mat: &SinkMatch<'_>) -> io::Result<bool> {
+        let line = String::from_utf8_lossy(mat.bytes());
+        self.lines.push((
+            mat.line_number().unwrap_or_default(),
+            line.trim_end().to_string(),
+        ));
+        Ok(true)
+    }
+
+    fn binary_data(&mut self, _searcher: &Searcher, _binary_byte_offset: u64) -> io::Result<bool> {
+        self.binary = true;
+        Ok(false)
+    }
+}
+
+fn with_path(scanned_urls: Vec<ScannedUrl>, path: &Path) -> Vec<ScannedUrl> {
+    scanned_urls
+        .into_iter()
+        .map(|scanned_url| ScannedUrl {
+            path: path.to_path_buf(),
+            ..scanned_url
+        })
         .collect()
 }
 
+// Symbolic links to a file are scanned even when the links are not followed
+fn is_scanned_file(entry: &DirEntry) -> bool {
+    match entry.file_type() {
+        Some(file_type) if file_type.is_symlink() => entry.path().is_file(),
+        Some(file_type) => file_type.is_file(),
+        None => false,
+    }
+}
+
+fn is_html_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| HTML_EXTENSIONS.contains(&extension.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+// 1-based line and column, in characters, of a byte offset
+fn line_and_column(content: &str, offset: usize) -> (u64, usize) {
+    let before = &content[..offset];
+    let line_start = before.rfind('\n').map(|position| position + 1).unwrap_or(0);
+    (
+        before.matches('\n').count() as u64 + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ProseSyntax {
+    Markdown,
+    AsciiDoc,
+}
+
+fn prose_syntax(path: &Path) -> Option<ProseSyntax> {
+    let extension = path.extension()?.to_str()?.to_lowercase();
+    match extension.as_str() {
+        extension if MARKDOWN_EXTENSIONS.contains(&extension) => Some(ProseSyntax::Markdown),
+        extension if ASCIIDOC_EXTENSIONS.contains(&extension) => Some(ProseSyntax::AsciiDoc),
+        _ => None,
+    }
+}
+
+// Delimiter of a Markdown (``` or ~~~) or AsciiDoc (----, ...., ++++) code block
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CodeFence {
+    marker: char,
+    length: usize,
+    // Markdown opening fences may be followed by the language
+    with_info: bool,
+}
+
+impl CodeFence {
+    fn closes(&self, opening: &CodeFence) -> bool {
+        match self.marker {
+            '`' | '~' => {
+                self.marker == opening.marker && self.length >= opening.length && !self.with_info
+            }
+            _ => self.marker == opening.marker && self.length == opening.length,
+        }
+    }
+}
+
+// A Markdown '----' is a horizontal rule, not a code block
+fn code_fence(syntax: ProseSyntax, line: &str) -> Option<CodeFence> {
+    let markers = match syntax {
+        ProseSyntax::Markdown => ['`', '~'].as_slice(),
+        ProseSyntax::AsciiDoc => ['-', '.', '+'].as_slice(),
+    };
+    let marker = line.chars().next().filter(|c| markers.contains(c))?;
+    let length = line.chars().take_while(|c| *c == marker).count();
+    let with_info = !line[length..].trim().is_empty();
+    let fence = CodeFence {
+        marker,
+        length,
+        with_info,
+    };
+    match marker {
+        '`' | '~' if length >= 3 => Some(fence),
+        '-' | '.' | '+' if length >= 4 && !with_info => Some(fence),
+        _ => None,
+    }
+}
+
+// Markdown (# Title) and AsciiDoc (== Title, or # Title like Markdown) headings
+fn extract_heading(syntax: ProseSyntax, line: &str) -> Option<String> {
+    let marker = line.chars().next().filter(|c| match syntax {
+        ProseSyntax::Markdown => *c == '#',
+        ProseSyntax::AsciiDoc => *c == '#' || *c == '=',
+    })?;
+    let level = line.chars().take_while(|c| *c == marker).count();
+    let title = line[level..].strip_prefix(' ')?;
+    let title = match marker {
+        '#' => title.trim_end_matches('#'),
+        _ => title,
+    }
+    .trim();
+
+    match level <= 6 && !title.is_empty() {
+        true => Some(title.to_string()),
+        false => None,
+    }
+}
+
+// [text](url) : the text is right before the url
+fn markdown_link_text(before_url: &str) -> Option<String> {
+    let before_text = before_url.strip_suffix("](")?;
+    let text_start = before_text.rfind('[')?;
+    non_empty_text(&before_text[text_start + 1..])
+}
+
+// url[text] : the text is right after the url
+fn asciidoc_link_text(after_url: &str) -> Option<String> {
+    let text = after_url.strip_prefix('[')?;
+    let text_end = text.find(']')?;
+    non_empty_text(&text[..text_end])
+}
+
+fn non_empty_text(text: &str) -> Option<String> {
+    let text = text.trim();
+    match text.is_empty() {
+        true => None,
+        false => Some(text.to_string()),
+    }
+}
+
 // linkify leave some trailing text due to URL rfc and brackets in asciidoc syntax
 fn url_cleaner(url: String) -> String {
     match url.find('[') {
@@ -77,8 +715,10 @@ fn url_cleaner(url: String) -> String {
 
 #[cfg(test)]
 mod tests {
+    use std::fs;
     use std::fs::File;
     use std::io::Write;
+    use std::path::Path;
 
     use tempfile::tempdir;
 
@@ -90,7 +730,12 @@ mod tests {
     }
 
     fn assert_extracted_urls(line: &str, expected: Vec<String>) {
-        assert_eq!(expected, extract_urls(line.to_string()))
+        let actual: Vec<String> = UrlScanner::new()
+            .extract_urls_with_link_text(line)
+            .into_iter()
+            .map(|scanned_url| scanned_url.url)
+            .collect();
+        assert_eq!(expected, actual)
     }
 
     #[test]
@@ -102,7 +747,13 @@ mod tests {
 
         let options = url_scanner::UrlScannerOptions {
             ignore_files: vec![ignore1_path.clone()],
+            ignore_globs: vec![],
             scan_path: dir.path().to_path_buf(),
+            ignore_root: dir.path().to_path_buf(),
+            threads: 0,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
         };
 
         let mut file1 = File::create(dir.path().join("file1.txt"))?;
@@ -134,6 +785,187 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn scan_urls_with_ignore_globs() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join("drafts"))?;
+
+        let options = url_scanner::UrlScannerOptions {
+            ignore_files: vec![],
+            ignore_globs: vec_of_strings!["drafts/", "*.log"],
+            scan_path: dir.path().to_path_buf(),
+            ignore_root: dir.path().to_path_buf(),
+            threads: 0,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
+        };
+
+        let mut file1 = File::create(dir.path().join("file1.txt"))?;
+        writeln!(file1, "Got to https://site1.tld")?;
+        let mut file2 = File::create(dir.path().join("drafts").join("file2.txt"))?;
+        writeln!(file2, "Got to https://site2.tld")?;
+        let mut file3 = File::create(dir.path().join("file3.log"))?;
+        writeln!(file3, "Got to https://site3.tld")?;
+
+        assert_eq!(
+            vec_of_strings!["https://site1.tld"],
+            scan_urls(options.clone())
+        );
+
+        // Anchored globs stay relative to the ignore root when a subfolder is scanned
+        let anchored_options = UrlScannerOptions {
+            ignore_globs: vec_of_strings!["/drafts/file2.txt"],
+            scan_path: dir.path().join("drafts"),
+            ..options
+        };
+        assert!(scan_urls(anchored_options).is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn scan_urls_skip_binary_files() -> Result<()> {
+        let dir = tempdir()?;
+
+        fs::write(
+            dir.path().join("binary.bin"),
+            b"Got to https://site1.tld\n\x00\x01\x02",
+        )?;
+        fs::write(
+            dir.path().join("latin1.txt"),
+            b"Caf\xe9 at https://site2.tld\n",
+        )?;
+
+        assert_eq!(
+            vec_of_strings!["https://site2.tld"],
+            scan_urls(fixture_options(dir.path(), 0))
+        );
+
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_urls_of_symlinked_files() -> Result<()> {
+        let dir = tempdir()?;
+        let outside = tempdir()?;
+
+        fs::write(
+            outside.path().join("notes.txt"),
+            "Go to https://site1.tld\n",
+        )?;
+        std::os::unix::fs::symlink(
+            outside.path().join("notes.txt"),
+            dir.path().join("notes.txt"),
+        )?;
+        fs::create_dir(outside.path().join("docs"))?;
+        fs::write(
+            outside.path().join("docs").join("index.txt"),
+            "Go to https://site2.tld\n",
+        )?;
+        std::os::unix::fs::symlink(outside.path().join("docs"), dir.path().join("docs"))?;
+
+        assert_eq!(
+            vec_of_strings!["https://site1.tld"],
+            scan_urls(fixture_options(dir.path(), 0))
+        );
+        assert_eq!(
+            vec![dir.path().join("notes.txt")],
+            list_files(fixture_options(dir.path(), 0))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scan_urls_from_html() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join("guide"))?;
+        fs::write(
+            dir.path().join("guide").join("index.html"),
+            r#"<html>
+<head><link rel="stylesheet" href="../style.css"></head>
+<body>
+<a href="https://site.tld/search?q=1&amp;page=2">Search</a>
+<a href="install.html">Install guide</a> or https://text.tld/?a=1&amp;b=2
+</body>
+</html>
+"#,
+        )?;
+
+        let scanned_urls = scan_urls_with_context(fixture_options(dir.path(), 1));
+        let urls: Vec<String> = scanned_urls.iter().map(|url| url.url.clone()).collect();
+        assert_eq!(
+            vec_of_strings![
+                "https://site.tld/search?q=1&page=2",
+                "https://text.tld/?a=1&b=2"
+            ],
+            urls
+        );
+        assert_eq!(4, scanned_urls[0].line);
+        assert_eq!(10, scanned_urls[0].column);
+        assert_eq!(Some("Search".to_string()), scanned_urls[0].link_text);
+
+        let options = UrlScannerOptions {
+            site_root: Some(Url::parse("https://docs.tld/v1/")?),
+            ..fixture_options(dir.path(), 1)
+        };
+        assert_eq!(
+            vec_of_strings![
+                "https://docs.tld/v1/guide/install.html",
+                "https://docs.tld/v1/style.css",
+                "https://site.tld/search?q=1&page=2",
+                "https://text.tld/?a=1&b=2"
+            ],
+            scan_urls(options)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scan_urls_with_selection() -> Result<()> {
+        let dir = tempdir()?;
+        fs::create_dir_all(dir.path().join("deep").join("deeper"))?;
+        fs::write(dir.path().join("README.md"), "Got to https://site1.tld\n")?;
+        fs::write(dir.path().join("notes.txt"), "Got to https://site2.tld\n")?;
+        fs::write(dir.path().join(".hidden.md"), "Got to https://site3.tld\n")?;
+        fs::write(
+            dir.path().join("deep").join("deeper").join("doc.md"),
+            "Got to https://site4.tld\n",
+        )?;
+
+        let options = UrlScannerOptions {
+            selection: ScanSelection {
+                types: vec_of_strings!["md"],
+                max_depth: Some(1),
+                ..ScanSelection::default()
+            },
+            ..fixture_options(dir.path(), 0)
+        };
+        assert_eq!(vec_of_strings!["https://site1.tld"], scan_urls(options));
+
+        let options = UrlScannerOptions {
+            selection: ScanSelection {
+                types_not: vec_of_strings!["txt"],
+                hidden: true,
+                ..ScanSelection::default()
+            },
+            ..fixture_options(dir.path(), 0)
+        };
+        assert_eq!(
+            vec_of_strings![
+                "https://site1.tld",
+                "https://site3.tld",
+                "https://site4.tld"
+            ],
+            scan_urls(options)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn scan_urls_with_two_ignore_files() -> Result<()> {
         let dir = tempdir()?;
@@ -146,7 +978,13 @@ mod tests {
 
         let options = url_scanner::UrlScannerOptions {
             ignore_files: vec![ignore1_path.clone(), ignore2_path.clone()],
+            ignore_globs: vec![],
             scan_path: dir.path().to_path_buf(),
+            ignore_root: dir.path().to_path_buf(),
+            threads: 0,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
         };
 
         let mut file1 = File::create(dir.path().join("file1.txt"))?;
@@ -171,6 +1009,283 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn scan_urls_with_headings_and_link_texts() -> Result<()> {
+        let dir = tempdir()?;
+        let options = url_scanner::UrlScannerOptions {
+            ignore_files: vec![],
+            ignore_globs: vec![],
+            scan_path: dir.path().to_path_buf(),
+            ignore_root: dir.path().to_path_buf(),
+            threads: 0,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
+        };
+
+        let mut markdown = File::create(dir.path().join("README.md"))?;
+        let content = r#"
+# Project
+
+Go to https://site1.tld
+
+## Deployment
+
+```shell
+# Not a heading either
+curl https://site5.tld
+```
+
+Read the [runbook](https://site2.tld)
+
+----
+
+# Monitoring
+
+Go to https://site6.tld
+        "#;
+        writeln!(markdown, "{}", content)?;
+
+        let mut asciidoc = File::create(dir.path().join("README.adoc"))?;
+        let content = r#"
+== Monitoring
+
+----
+== Not a heading
+----
+
+See https://site3.tld[Grafana dashboards]
+        "#;
+        writeln!(asciidoc, "{}", content)?;
+
+        let mut text = File::create(dir.path().join("file.txt"))?;
+        let content = r#"
+# Not a heading
+Got to https://site4.tld
+        "#;
+        writeln!(text, "{}", content)?;
+
+        let actual: Vec<Option<String>> = scan_urls_with_context(options)
+            .iter()
+            .map(|scanned_url| scanned_url.suggested_context())
+            .collect();
+        assert_eq!(
+            vec![
+                Some("project".to_string()),
+                Some("deployment runbook".to_string()),
+                Some("monitoring grafana dashboards".to_string()),
+                None,
+                Some("deployment".to_string()),
+                Some("monitoring".to_string())
+            ],
+            actual
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn scan_urls_with_sources() -> Result<()> {
+        let dir = tempdir()?;
+        let options = url_scanner::UrlScannerOptions {
+            ignore_files: vec![],
+            ignore_globs: vec![],
+            scan_path: dir.path().to_path_buf(),
+            ignore_root: dir.path().to_path_buf(),
+            threads: 0,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
+        };
+
+        let mut file = File::create(dir.path().join("file.txt"))?;
+        writeln!(file, "Got to https://site1.tld")?;
+        writeln!(file)?;
+        writeln!(file, "    Got to https://site2.tld and https://site1.tld")?;
+
+        let actual: Vec<String> = scan_urls_with_context(options)
+            .iter()
+            .map(|scanned_url| format!("{} {}", scanned_url.url, scanned_url.source(dir.path())))
+            .collect();
+        let path = "file.txt";
+        assert_eq!(
+            vec![
+                format!("https://site1.tld {}:1:8", path),
+                format!("https://site1.tld {}:3:34", path),
+                format!("https://site2.tld {}:3:12", path),
+            ],
+            actual
+        );
+
+        Ok(())
+    }
+
+    // A tree of markdown files, with some URLs shared between files
+    fn generate_fixture(root: &Path, files: usize) -> Result<()> {
+        for index in 0..files {
+            let folder = root.join(format!("folder{}", index % 50));
+            fs::create_dir_all(&folder)?;
+            let mut file = File::create(folder.join(format!("file{}.md", index)))?;
+            writeln!(file, "# Section {}", index)?;
+            for line in 0..40 {
+                match line % 4 {
+                    0 => writeln!(file, "Got to https://site{}.tld/page{}", index % 100, line)?,
+                    _ => writeln!(file, "Some text without any link on line {}", line)?,
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn fixture_options(root: &Path, threads: usize) -> UrlScannerOptions {
+        url_scanner::UrlScannerOptions {
+            ignore_files: vec![],
+            ignore_globs: vec![],
+            scan_path: root.to_path_buf(),
+            ignore_root: root.to_path_buf(),
+            threads,
+            selection: ScanSelection::default(),
+            comments_only: vec![],
+            site_root: None,
+        }
+    }
+
+    #[test]
+    fn parallel_scan_matches_sequential_scan() -> Result<()> {
+        let dir = tempdir()?;
+        generate_fixture(dir.path(), 200)?;
+
+        let sequential = scan_urls_with_context(fixture_options(dir.path(), 1));
+        let parallel = scan_urls_with_context(fixture_options(dir.path(), 0));
+        assert_eq!(2000, sequential.len());
+        assert_eq!(sequential, parallel);
+
+        let urls = scan_urls(fixture_options(dir.path(), 0));
+        assert_eq!(1000, urls.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn path_of_source() {
+        assert_eq!(
+            PathBuf::from("docs/index.md"),
+            ScannedUrl::path_of_source("docs/index.md:3:12")
+        );
+        assert_eq!(
+            PathBuf::from("C:/docs/index.md"),
+            ScannedUrl::path_of_source("C:/docs/index.md:3:12")
+        );
+    }
+
+    #[test]
+    fn scan_urls_with_cache_only_parse_changed_files() -> Result<()> {
+        let dir = tempdir()?;
+        let mut file1 = File::create(dir.path().join("file1.txt"))?;
+        writeln!(file1, "Got to https://site1.tld")?;
+        let mut file2 = File::create(dir.path().join("file2.txt"))?;
+        writeln!(file2, "Got to https://site2.tld")?;
+
+        let mut cache = ScanCache::empty_cache();
+        let (scanned_urls, stats) =
+            scan_urls_with_cache(fixture_options(dir.path(), 0), &mut cache);
+        assert_eq!(2, scanned_urls.len());
+        assert_eq!(2, stats.scanned_files);
+        assert_eq!(0, stats.reused_files);
+
+        let (cached_urls, stats) = scan_urls_with_cache(fixture_options(dir.path(), 0), &mut cache);
+        assert_eq!(scanned_urls, cached_urls);
+        assert_eq!(0, stats.scanned_files);
+        assert_eq!(2, stats.reused_files);
+
+        let mut file2 = File::create(dir.path().join("file2.txt"))?;
+        writeln!(
+            file2,
+            "Got to https://site3.tld and later https://site4.tld"
+        )?;
+        fs::remove_file(dir.path().join("file1.txt"))?;
+        let (scanned_urls, stats) =
+            scan_urls_with_cache(fixture_options(dir.path(), 0), &mut cache);
+        assert_eq!(
+            vec!["https://site3.tld", "https://site4.tld"],
+            scanned_urls
+                .iter()
+                .map(|scanned_url| scanned_url.url.as_str())
+                .collect::<Vec<&str>>()
+        );
+        assert_eq!(dir.path().join("file2.txt"), scanned_urls[0].path);
+        assert_eq!(1, stats.scanned_files);
+        assert_eq!(1, cache.len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn extract_markdown_and_asciidoc_headings() {
+        let markdown = ProseSyntax::Markdown;
+        let asciidoc = ProseSyntax::AsciiDoc;
+        assert_eq!(
+            Some("Title".to_string()),
+            extract_heading(markdown, "# Title")
+        );
+        assert_eq!(
+            Some("Title".to_string()),
+            extract_heading(markdown, "### Title ###")
+        );
+        assert_eq!(None, extract_heading(markdown, "== Title"));
+        assert_eq!(
+            Some("Title".to_string()),
+            extract_heading(asciidoc, "== Title")
+        );
+        assert_eq!(None, extract_heading(markdown, "#Title"));
+        assert_eq!(None, extract_heading(markdown, "####### Title"));
+        assert_eq!(None, extract_heading(markdown, "Got to https://site.tld"));
+    }
+
+    #[test]
+    fn extract_code_fences() {
+        let markdown = ProseSyntax::Markdown;
+        let asciidoc = ProseSyntax::AsciiDoc;
+        let opening = code_fence(markdown, "```rust").unwrap();
+        assert!(code_fence(markdown, "```").unwrap().closes(&opening));
+        assert!(!code_fence(markdown, "~~~").unwrap().closes(&opening));
+        assert!(!code_fence(markdown, "```shell").unwrap().closes(&opening));
+        let opening = code_fence(asciidoc, "----").unwrap();
+        assert!(code_fence(asciidoc, "----").unwrap().closes(&opening));
+        assert!(!code_fence(asciidoc, "-----").unwrap().closes(&opening));
+        assert_eq!(None, code_fence(markdown, "``"));
+        assert_eq!(None, code_fence(markdown, "----"));
+        assert_eq!(None, code_fence(asciidoc, "```"));
+        assert_eq!(None, code_fence(asciidoc, "--- title"));
+        assert_eq!(None, code_fence(asciidoc, "# Title"));
+    }
+
+    #[test]
+    fn columns_are_counted_in_characters() {
+        let columns: Vec<usize> = UrlScanner::new()
+            .extract_urls_with_link_text("Déjà vu → https://site.tld")
+            .into_iter()
+            .map(|scanned_url| scanned_url.column)
+            .collect();
+        assert_eq!(vec![11], columns);
+        assert_eq!((2, 5), line_and_column("été\nà é https://site.tld", 12));
+    }
+
+    #[test]
+    fn extract_link_texts() {
+        let link_texts: Vec<Option<String>> = UrlScanner::new()
+            .extract_urls_with_link_text(
+                "Got to [Site](https://site.tld), https://site2.tld[Site 2] and https://site3.tld",
+            )
+            .into_iter()
+            .map(|scanned_url| scanned_url.link_text)
+            .collect();
+        assert_eq!(
+            vec![Some("Site".to_string()), Some("Site 2".to_string()), None],
+            link_texts
+        );
+    }
+
     #[test]
     fn extract_no_url() {
         assert_extracted_urls("Got to www.site.tld", vec_of_strings![]);
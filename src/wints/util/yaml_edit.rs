@@ -0,0 +1,457 @@
+use serde_yaml::{Mapping, Value};
+
+// Rewrite a block-style YAML document so it deserialises to the updated value, with minimal
+// edits: untouched entries and items keep their comments, order and formatting.
+// None when the document can't be edited in place, the caller should write it from scratch
+pub fn edit_yaml(original: &str, updated: &Value) -> Option<String> {
+    let current: Value = serde_yaml::from_str(original).ok()?;
+    let lines: Vec<String> = original.lines().map(str::to_string).collect();
+    // A document marker stays before the root node
+    let start = match first_significant(&lines) {
+        Some(index) if lines[index].trim() == "---" => index + 1,
+        _ => 0,
+    };
+    let mut edited: Vec<String> = lines[..start].to_vec();
+    edited.extend(edit_block(&lines[start..], &current, updated)?);
+    let mut edited = edited.join("\n");
+    if original.ends_with('\n') || original.is_empty() {
+        edited.push('\n');
+    }
+    match serde_yaml::from_str::<Value>(&edited) {
+        Ok(value) if value == *updated => Some(edited),
+        _ => None,
+    }
+}
+
+fn edit_block(lines: &[String], current: &Value, updated: &Value) -> Option<Vec<String>> {
+    if current == updated {
+        return Some(lines.to_vec());
+    }
+    let first = first_significant(lines)?;
+    let indent = indent_of(&lines[first]);
+    let text = &lines[first][indent..];
+    match (current, updated) {
+        (Value::Sequence(current), Value::Sequence(updated)) if is_sequence_item(text) => {
+            edit_sequence(lines, indent, current, updated)
+        }
+        (Value::Mapping(current), Value::Mapping(updated)) if split_key(text).is_some() => {
+            edit_mapping(lines, indent, current, updated)
+        }
+        _ => None,
+    }
+}
+
+fn edit_mapping(
+    lines: &[String],
+    indent: usize,
+    current: &Mapping,
+    updated: &Mapping,
+) -> Option<Vec<String>> {
+    let entries = split_block(lines, indent, |text| !is_sequence_item(text))?;
+    let keys = entries
+        .iter()
+        .map(|(start, _)| split_key(&lines[*start][indent..]).map(|(key, _)| key))
+        .collect::<Option<Vec<Value>>>()?;
+    if keys.len() != current.len() || keys.iter().any(|key| !current.contains_key(key)) {
+        return None;
+    }
+
+    let mut edited = vec![];
+    let mut position = 0;
+    for ((start, end), key) in entries.into_iter().zip(keys) {
+        edited.extend_from_slice(&lines[position..start]);
+        position = end;
+        let current_value = &current[&key];
+        match updated.get(&key) {
+            None => {}
+            Some(value) if value == current_value => edited.extend_from_slice(&lines[start..end]),
+            Some(value) => edited.extend(edit_entry(
+                &lines[start..end],
+                indent,
+                &key,
+                current_value,
+                value,
+            )?),
+        }
+    }
+    for (key, value) in updated {
+        if !current.contains_key(key) {
+            edited.extend(render_entry(indent, key, value)?);
+        }
+    }
+    edited.extend_from_slice(&lines[position..]);
+    Some(edited)
+}
+
+// An inline value is replaced, a block value is edited
+fn edit_entry(
+    lines: &[String],
+    indent: usize,
+    key: &Value,
+    current: &Value,
+    updated: &Value,
+) -> Option<Vec<String>> {
+    let (_, rest) = split_key(&lines[0][indent..])?;
+    let rest = rest.trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return render_entry(indent, key, updated);
+    }
+    match edit_block(&lines[1..], current, updated) {
+        Some(value_lines) => {
+            let mut edited = vec![lines[0].clone()];
+            edited.extend(value_lines);
+            Some(edited)
+        }
+        None => render_entry(indent, key, updated),
+    }
+}
+
+// Unchanged items are kept, the others are edited, removed or inserted in place
+fn edit_sequence(
+    lines: &[String],
+    indent: usize,
+    current: &[Value],
+    updated: &[Value],
+) -> Option<Vec<String>> {
+    let items = split_block(lines, indent, is_sequence_item)?;
+    if items.len() != current.len() {
+        return None;
+    }
+
+    let mut anchors = matching_items(current, updated);
+    anchors.push((current.len(), updated.len()));
+    let mut edited = vec![];
+    let mut position = 0;
+    let (mut current_index, mut updated_index) = (0, 0);
+    for (current_anchor, updated_anchor) in anchors {
+        let changed = (current_anchor - current_index).min(updated_anchor - updated_index);
+        for offset in 0..(current_anchor - current_index) {
+            let (start, end) = items[current_index + offset];
+            edited.extend_from_slice(&lines[position..start]);
+            position = end;
+            if offset < changed {
+                edited.extend(edit_item(
+                    &lines[start..end],
+                    indent,
+                    &current[current_index + offset],
+                    &updated[updated_index + offset],
+                )?);
+            }
+        }
+        for value in &updated[(updated_index + changed)..updated_anchor] {
+            edited.extend(render_item(indent, value)?);
+        }
+        if current_anchor < current.len() {
+            let (_, end) = items[current_anchor];
+            edited.extend_from_slice(&lines[position..end]);
+            position = end;
+        }
+        current_index = current_anchor + 1;
+        updated_index = updated_anchor + 1;
+    }
+    edited.extend_from_slice(&lines[position..]);
+    Some(edited)
+}
+
+// The content of an item is edited as a block where the dash is a space
+fn edit_item(
+    lines: &[String],
+    indent: usize,
+    current: &Value,
+    updated: &Value,
+) -> Option<Vec<String>> {
+    let after_dash = &lines[0][indent + 1..];
+    let content = after_dash.trim_start();
+    if content.is_empty() || content.starts_with('#') {
+        return match edit_block(&lines[1..], current, updated) {
+            Some(value_lines) => {
+                let mut edited = vec![lines[0].clone()];
+                edited.extend(value_lines);
+                Some(edited)
+            }
+            None => render_item(indent, updated),
+        };
+    }
+    let content_indent = indent + 1 + after_dash.len() - content.len();
+    let mut content_lines = lines.to_vec();
+    content_lines[0].replace_range(indent..indent + 1, " ");
+    match edit_block(&content_lines, current, updated) {
+        Some(mut edited)
+            if edited
+                .first()
+                .is_some_and(|first| indent_of(first) == content_indent) =>
+        {
+            edited[0].replace_range(indent..indent + 1, "-");
+            Some(edited)
+        }
+        _ => render_item(indent, updated),
+    }
+}
+
+fn render_entry(indent: usize, key: &Value, value: &Value) -> Option<Vec<String>> {
+    let mut mapping = Mapping::new();
+    mapping.insert(key.clone(), value.clone());
+    render(indent, &Value::Mapping(mapping))
+}
+
+fn render_item(indent: usize, value: &Value) -> Option<Vec<String>> {
+    render(indent, &Value::Sequence(vec![value.clone()]))
+}
+
+fn render(indent: usize, value: &Value) -> Option<Vec<String>> {
+    let text = serde_yaml::to_string(value).ok()?;
+    Some(
+        text.lines()
+            .map(|line| format!("{}{}", " ".repeat(indent), line))
+            .collect(),
+    )
+}
+
+// Line ranges of the entries or items of a block, from their first line to their last content,
+// the comments and blank lines after them are left between the ranges
+fn split_block(
+    lines: &[String],
+    indent: usize,
+    starts_node: impl Fn(&str) -> bool,
+) -> Option<Vec<(usize, usize)>> {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for (index, line) in lines.iter().enumerate() {
+        if !is_significant(line) {
+            continue;
+        }
+        let line_indent = indent_of(line);
+        if line_indent < indent {
+            return None;
+        }
+        if line_indent == indent && starts_node(&line[indent..]) {
+            ranges.push((index, index + 1));
+        } else {
+            ranges.last_mut()?.1 = index + 1;
+        }
+    }
+    Some(ranges)
+}
+
+// Pairs of equal items, in order, after the common prefix and suffix
+fn matching_items(current: &[Value], updated: &[Value]) -> Vec<(usize, usize)> {
+    let prefix = current
+        .iter()
+        .zip(updated)
+        .take_while(|(current, updated)| current == updated)
+        .count();
+    let suffix = current[prefix..]
+        .iter()
+        .rev()
+        .zip(updated[prefix..].iter().rev())
+        .take_while(|(current, updated)| current == updated)
+        .count();
+    let current_middle = &current[prefix..current.len() - suffix];
+    let updated_middle = &updated[prefix..updated.len() - suffix];
+
+    // Longest common subsequence of the middle items
+    let (rows, columns) = (current_middle.len(), updated_middle.len());
+    let mut lengths = vec![vec![0usize; columns + 1]; rows + 1];
+    for row in (0..rows).rev() {
+        for column in (0..columns).rev() {
+            lengths[row][column] = match current_middle[row] == updated_middle[column] {
+                true => lengths[row + 1][column + 1] + 1,
+                false => lengths[row + 1][column].max(lengths[row][column + 1]),
+            };
+        }
+    }
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|index| (index, index)).collect();
+    let (mut row, mut column) = (0, 0);
+    while row < rows && column < columns {
+        if current_middle[row] == updated_middle[column] {
+            pairs.push((prefix + row, prefix + column));
+            row += 1;
+            column += 1;
+        } else if lengths[row + 1][column] >= lengths[row][column + 1] {
+            row += 1;
+        } else {
+            column += 1;
+        }
+    }
+    pairs.extend((0..suffix).map(|offset| {
+        (
+            current.len() - suffix + offset,
+            updated.len() - suffix + offset,
+        )
+    }));
+    pairs
+}
+
+// The key of a 'key: value' line, and what follows its colon
+fn split_key(text: &str) -> Option<(Value, &str)> {
+    let key_end = match text.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let mut escaped = false;
+            let mut end = None;
+            for (index, c) in text.char_indices().skip(1) {
+                match c {
+                    '\\' if quote == '"' && !escaped => escaped = true,
+                    c if c == quote && !escaped => {
+                        end = Some(index + 1);
+                        break;
+                    }
+                    _ => escaped = false,
+                }
+            }
+            end?
+        }
+        '-' | '#' | '?' | '{' | '[' | '&' | '*' | '!' | '|' | '>' => return None,
+        _ => text
+            .match_indices(':')
+            .map(|(index, _)| index)
+            .find(|index| {
+                text[index + 1..]
+                    .chars()
+                    .next()
+                    .is_none_or(char::is_whitespace)
+            })?,
+    };
+    let rest = text[key_end..].strip_prefix(':')?;
+    if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+        return None;
+    }
+    let key: Value = serde_yaml::from_str(&text[..key_end]).ok()?;
+    Some((key, rest))
+}
+
+fn is_sequence_item(text: &str) -> bool {
+    text == "-" || text.starts_with("- ")
+}
+
+fn is_significant(line: &str) -> bool {
+    let text = line.trim();
+    !text.is_empty() && !text.starts_with('#')
+}
+
+fn first_significant(lines: &[String]) -> Option<usize> {
+    lines.iter().position(|line| is_significant(line))
+}
+
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_yaml::Value;
+
+    use crate::util::yaml_edit::*;
+
+    fn edit(original: &str, updated: &str) -> Option<String> {
+        let updated: Value = serde_yaml::from_str(updated).unwrap();
+        edit_yaml(original, &updated)
+    }
+
+    #[test]
+    fn append_an_url_to_a_context() {
+        let original = r#"# Links of the team
+version: 1
+elements:
+# Where to start
+- context: docs
+  urls:
+  - https://docs.rs   # API docs
+  - https://crates.io
+- context: ci
+  urls: [https://ci.tld]
+"#;
+        let updated = r#"
+version: 1
+elements:
+- context: docs
+  urls:
+  - https://docs.rs
+  - https://crates.io
+  - https://lib.rs
+- context: ci
+  urls: [https://ci.tld]
+"#;
+        assert_eq!(
+            Some(
+                r#"# Links of the team
+version: 1
+elements:
+# Where to start
+- context: docs
+  urls:
+  - https://docs.rs   # API docs
+  - https://crates.io
+  - https://lib.rs
+- context: ci
+  urls: [https://ci.tld]
+"#
+                .to_string()
+            ),
+            edit(original, updated)
+        );
+    }
+
+    #[test]
+    fn add_and_remove_contexts() {
+        let original = r#"version: 1
+elements:
+  - context: ci   # pipelines
+    urls:
+      - https://ci.tld
+  - context: old
+    urls:
+      - https://old.tld
+"#;
+        let updated = r#"
+version: 1
+elements:
+  - context: ci
+    urls:
+      - https://ci.tld
+      - https://ci.tld/nightly
+  - context: new
+    urls:
+      - https://new.tld
+sources:
+  https://new.tld:
+    - README.md:3:1
+"#;
+        assert_eq!(
+            Some(
+                r#"version: 1
+elements:
+  - context: ci   # pipelines
+    urls:
+      - https://ci.tld
+      - https://ci.tld/nightly
+  - context: new
+    urls:
+      - https://new.tld
+sources:
+  https://new.tld:
+  - README.md:3:1
+"#
+                .to_string()
+            ),
+            edit(original, updated)
+        );
+    }
+
+    #[test]
+    fn replace_inline_values() {
+        let original = "version: 1 # format\nelements: []\n";
+        let updated = "version: 1\nelements:\n- context: docs\n  urls:\n  - https://docs.rs\n";
+        assert_eq!(
+            Some(
+                "version: 1 # format\nelements:\n- context: docs\n  urls:\n  - https://docs.rs\n"
+                    .to_string()
+            ),
+            edit(original, updated)
+        );
+    }
+
+    #[test]
+    fn unsupported_documents() {
+        assert_eq!(None, edit("{version: 1}", "version: 2"));
+        assert_eq!(None, edit("version: [", "version: 2"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::fs;
+
 use anyhow::Result;
 use assert_cmd::prelude::*;
 use predicate::str::contains;
@@ -201,3 +203,238 @@ fn add_to_some_global_module() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn add_canonical_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("HTTPS://Site.TLD/?utm_source=mail")
+        .arg("context");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'HTTPS://Site.TLD/?utm_source=mail' have been added to 'context' in 'main'",
+    ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld/")
+        .arg("context");
+
+    wints.cmd.assert().success().stdout(contains(
+        " ℹ️ 'https://site.tld/' is already in 'context' in 'main'",
+    ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld/")
+        .arg("another context");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'https://site.tld/' have been added to 'another context' in 'main'",
+    ));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("modules").join("main.yaml"))?;
+    assert_eq!(
+        content,
+        r#"version: 1
+elements:
+- context: context
+  urls:
+  - HTTPS://Site.TLD/?utm_source=mail
+- context: another context
+  urls:
+  - https://site.tld/
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn add_only_writes_the_changed_module() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let global_module = "version: 1\nelements:\n- context: docs\n  urls: [https://docs.rs]\n";
+    wints.new_file_in_folder("home_dir_config_dir/modules", "tools.yaml", global_module)?;
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "main.yaml",
+        "version: 1\nelements: []",
+    )?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("https://site.tld")
+        .arg("context");
+
+    let main_module = wints.local_config_dir.join("modules").join("main.yaml");
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " 🌀 Add 'https://site.tld' to 'context' in 'main'",
+        ))
+        .stdout(contains(format!(" 🌀 Write '{}'", main_module.display())))
+        .stdout(contains("tools.yaml").not());
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld")
+        .arg("context");
+
+    wints.cmd.assert().success();
+    assert_eq!(
+        format!("{}\n", global_module),
+        fs::read_to_string(wints.home_dir_config_dir.join("modules").join("tools.yaml"))?
+    );
+    assert!(fs::read_to_string(main_module)?.contains("https://site.tld"));
+
+    Ok(())
+}
+
+#[test]
+fn add_keeps_the_comments_of_the_module() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "main.yaml",
+        r#"version: 1
+elements:
+# Rust documentation
+- context: docs
+  urls:
+  - https://docs.rs # API
+- context: ci
+  urls:
+  - https://ci.tld"#,
+    )?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://lib.rs")
+        .arg("docs");
+
+    wints.cmd.assert().success();
+    assert_eq!(
+        r#"version: 1
+elements:
+# Rust documentation
+- context: docs
+  urls:
+  - https://docs.rs # API
+  - https://lib.rs
+- context: ci
+  urls:
+  - https://ci.tld
+"#,
+        fs::read_to_string(wints.local_config_dir.join("modules").join("main.yaml"))?
+    );
+
+    Ok(())
+}
+
+#[test]
+fn add_dry_run_shows_the_diff() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "main.yaml",
+        "version: 1\nelements:\n- context: docs\n  urls:\n  - https://docs.rs\n",
+    )?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("https://site.tld")
+        .arg("docs");
+
+    let main_module = wints.local_config_dir.join("modules").join("main.yaml");
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            "+++ b/{}",
+            main_module.display().to_string().trim_start_matches('/')
+        )))
+        .stdout(contains("   - https://docs.rs\n+  - https://site.tld\n"));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--json")
+        .arg("https://site.tld")
+        .arg("docs");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(r#"{"changes":[{"after":"#))
+        .stdout(contains(r#""status":"modified""#))
+        .stdout(contains(r#""dry_run":true"#));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--json")
+        .arg("https://site.tld")
+        .arg("docs");
+
+    wints.cmd.assert().failure();
+
+    Ok(())
+}
@@ -197,3 +197,277 @@ fn using_global_module() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn git_template() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "repository/.git",
+        "config",
+        r#"[remote "origin"]
+	url = git@gitlab.com:group/project.git"#,
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--repository")
+        .arg(wints.current_dir.join("repository").display().to_string())
+        .arg("git");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " ℹ️ Using the remote 'origin' (gitlab.com/group/project)",
+        ))
+        .stdout(contains(
+            " 📝 A new 'main' module have been created with 6 contexts and 6 URLs",
+        ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("pull");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 🌀 Open https://gitlab.com/group/project/-/merge_requests",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn git_template_with_self_hosted_forge() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "repository/.git",
+        "config",
+        r#"[remote "origin"]
+	url = ssh://git@git.corp.dev:2222/team/repo.git"#,
+    )?;
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+forges:
+  git.corp.dev:
+    kind: gitea
+    web_url: https://code.corp.dev
+    wiki: ""
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--repository")
+        .arg(wints.current_dir.join("repository").display().to_string())
+        .arg("git");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 A new 'main' module have been created with 5 contexts and 5 URLs",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn git_template_outside_of_a_repository() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--repository")
+        .arg(wints.current_dir.display().to_string())
+        .arg("git");
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("is not in a git repository"));
+
+    Ok(())
+}
+
+const SERVICE_TEMPLATE: &str = r#"
+description: Standard service
+variables:
+  - name: service
+    description: Name of the service
+  - name: team
+    default: platform
+elements:
+  - context: "{{service}} docs"
+    urls:
+      - https://docs.corp.dev/{{team}}/{{service}}
+  - context: "{{service}} dashboard"
+    urls:
+      - https://grafana.corp.dev/d/{{service}}
+"#;
+
+#[test]
+fn user_template() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "home_dir_config_dir/templates",
+        "service.yaml",
+        SERVICE_TEMPLATE,
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--var")
+        .arg("service=billing")
+        .arg("service");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📝 Initialise 'main' with template 'service'..."))
+        .stdout(contains(
+            " 📝 A new 'main' module have been created with 2 contexts and 2 URLs",
+        ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("billing docs");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("https://docs.corp.dev/platform/billing"));
+
+    Ok(())
+}
+
+#[test]
+fn user_template_without_a_variable() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/templates",
+        "service.yaml",
+        SERVICE_TEMPLATE,
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("service");
+
+    wints.cmd.assert().failure().stderr(contains(
+        "missing value for variable 'service', set it with --var service=VALUE",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn list_templates() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/templates",
+        "service.yaml",
+        SERVICE_TEMPLATE,
+    )?;
+    wints.new_file_in_folder(
+        "home_dir_config_dir/templates",
+        "team.yaml",
+        "description: Team pages",
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--list-templates");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 5 templates:"))
+        .stdout(contains("    [built-in] empty, Module without URLs"))
+        .stdout(contains(
+            "    [local] service, Standard service (variables: service, team)",
+        ))
+        .stdout(contains("    [global] team, Team pages"));
+
+    Ok(())
+}
+
+#[test]
+fn existing_module() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "main.yaml",
+        "version: 1\nelements:\n- context: docs\n  urls:\n  - https://docs.rs",
+    )?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("default");
+
+    wints.cmd.assert().failure().stderr(contains(
+        "the module 'main' already exists, use --force to overwrite it",
+    ));
+    assert!(
+        std::fs::read_to_string(wints.local_config_dir.join("modules").join("main.yaml"))?
+            .contains("https://docs.rs")
+    );
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn where_from_a_subdirectory() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let project_dir = fs::canonicalize(&wints.current_dir)?;
+    wints.new_file_in_folder(".wints/modules", "main.yaml", "version: 1\nelements: []")?;
+    let subdir = wints.new_file_in_folder("src/bin", "main.rs", "")?;
+
+    wints
+        .cmd
+        .current_dir(&subdir)
+        .env_remove("WINTS_DIR")
+        .env_remove("WINTS_CEILING_DIRECTORIES")
+        .arg("where")
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            " 📍 Local configuration: {} (nearest one from the current directory)\n",
+            project_dir.join(".wints").display()
+        )))
+        .stdout(contains(format!(
+            " 📍 Global configuration: {} (set by --global-config), not created yet",
+            wints.home_dir_config_dir.display()
+        )));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .current_dir(&subdir)
+        .env_remove("WINTS_DIR")
+        .env_remove("WINTS_CEILING_DIRECTORIES")
+        .arg("add")
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld")
+        .arg("context");
+
+    wints.cmd.assert().success();
+    assert!(
+        fs::read_to_string(project_dir.join(".wints").join("modules").join("main.yaml"))?
+            .contains("https://site.tld")
+    );
+    assert!(!subdir.join(".wints").exists());
+
+    Ok(())
+}
+
+#[test]
+fn where_with_a_ceiling_directory() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(".wints/modules", "main.yaml", "version: 1\nelements: []")?;
+    let subdir = wints.new_file_in_folder("src/bin", "main.rs", "")?;
+
+    wints
+        .cmd
+        .current_dir(&subdir)
+        .env_remove("WINTS_DIR")
+        .env("WINTS_CEILING_DIRECTORIES", wints.current_dir.join("src"))
+        .arg("where")
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📍 Local configuration: .wints (none found from the current directory, created here when needed), not created yet",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn where_using_wints_dir() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(".wints/modules", "main.yaml", "version: 1\nelements: []")?;
+
+    wints
+        .cmd
+        .current_dir(&wints.current_dir)
+        .env("WINTS_DIR", &wints.local_config_dir)
+        .arg("where")
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(format!(
+        " 📍 Local configuration: {} (set by WINTS_DIR), not created yet",
+        wints.local_config_dir.display()
+    )));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .current_dir(&wints.current_dir)
+        .env("WINTS_DIR", &wints.local_config_dir)
+        .arg("where")
+        .arg("--config")
+        .arg(".wints")
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " 📍 Local configuration: .wints (set by --config)\n",
+        ))
+        .stdout(contains("WINTS_DIR").not());
+
+    Ok(())
+}
+
+#[test]
+fn where_using_xdg_folders() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let home_dir = wints.current_dir.join("home");
+    let xdg_dir = wints.current_dir.join("xdg");
+    wints
+        .cmd
+        .env("HOME", &home_dir)
+        .env("XDG_CONFIG_HOME", xdg_dir.join("config"))
+        .env("XDG_STATE_HOME", xdg_dir.join("state"))
+        .env_remove("WINTS_GLOBAL_DIR")
+        .arg("where")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            " 📍 Global configuration: {} (XDG configuration folder), not created yet",
+            xdg_dir.join("config").join("wints").display()
+        )))
+        .stdout(contains(format!(
+            " 📍 Global history: {}, not created yet",
+            xdg_dir.join("state").join("wints").display()
+        )))
+        .stdout(contains("Global cache").not());
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .env("HOME", &home_dir)
+        .env("WINTS_GLOBAL_DIR", &wints.home_dir_config_dir)
+        .arg("where")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            " 📍 Global configuration: {} (set by WINTS_GLOBAL_DIR), not created yet",
+            wints.home_dir_config_dir.display()
+        )))
+        .stdout(contains(format!(
+            " 📍 Global history: {}, not created yet",
+            wints.home_dir_config_dir.display()
+        )));
+
+    Ok(())
+}
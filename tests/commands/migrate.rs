@@ -0,0 +1,140 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+fn with_home(cmd: &mut Command, current_dir: &Path) {
+    cmd.env("HOME", current_dir.join("home"))
+        .env("XDG_CONFIG_HOME", current_dir.join("xdg").join("config"))
+        .env("XDG_STATE_HOME", current_dir.join("xdg").join("state"))
+        .env_remove("WINTS_GLOBAL_DIR");
+}
+
+#[test]
+fn migrate_the_legacy_folder() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let legacy_dir = wints.current_dir.join("home").join(".wints");
+    let config_dir = wints.current_dir.join("xdg").join("config").join("wints");
+    let state_dir = wints.current_dir.join("xdg").join("state").join("wints");
+    wints.new_file_in_folder("home/.wints", "ignore", "target")?;
+
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global")
+        .arg("https://site.tld")
+        .arg("context");
+
+    wints.cmd.assert().success();
+    assert!(legacy_dir.join("modules").join("main.yaml").exists());
+    assert!(legacy_dir.join("journal").exists());
+
+    wints.new_cmd()?;
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints
+        .cmd
+        .arg("where")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(format!(
+        " 📍 Global configuration: {} (legacy folder, move it to '{}' with 'wints migrate')",
+        legacy_dir.display(),
+        config_dir.display()
+    )));
+
+    wints.new_cmd()?;
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints.cmd.arg("migrate").arg("--dry-run");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(format!(
+            " 🌀 Move '{}' to '{}'",
+            legacy_dir.join("journal").display(),
+            state_dir.join("journal").display()
+        )))
+        .stdout(contains(format!(
+            " 🌀 Move '{}' to '{}'",
+            legacy_dir.join("modules").display(),
+            config_dir.join("modules").display()
+        )))
+        .stdout(contains(".lock").not());
+    assert!(legacy_dir.join("modules").exists());
+    assert!(!config_dir.exists());
+
+    wints.new_cmd()?;
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints.cmd.arg("migrate");
+
+    wints.cmd.assert().success().stdout(contains(format!(
+        " 📝 3 files have been moved from '{}'",
+        legacy_dir.display()
+    )));
+    assert!(!legacy_dir.exists());
+    assert_eq!("target\n", fs::read_to_string(config_dir.join("ignore"))?);
+    assert!(
+        fs::read_to_string(config_dir.join("modules").join("main.yaml"))?
+            .contains("https://site.tld")
+    );
+
+    wints.new_cmd()?;
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints
+        .cmd
+        .arg("undo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📝 1 files have been restored as before #1"));
+    assert!(!config_dir.join("modules").join("main.yaml").exists());
+    assert!(state_dir.join("journal").exists());
+
+    wints.new_cmd()?;
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints.cmd.arg("migrate");
+
+    wints.cmd.assert().success().stdout(contains(format!(
+        " 😢 There is no '{}' folder to migrate.",
+        legacy_dir.display()
+    )));
+
+    Ok(())
+}
+
+#[test]
+fn migrate_into_an_existing_folder() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder("home/.wints", "ignore", "target")?;
+    wints.new_file_in_folder("xdg/config/wints", "ignore", "dist")?;
+
+    with_home(&mut wints.cmd, &wints.current_dir);
+    wints.cmd.arg("migrate");
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("already exists, move the files of"));
+    assert!(wints.current_dir.join("home").join(".wints").exists());
+
+    Ok(())
+}
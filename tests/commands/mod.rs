@@ -1,6 +1,14 @@
 mod add;
 mod init;
+mod location;
+mod migrate;
+mod prune;
 mod scan;
 mod search;
+mod undo;
 mod url_ignore;
 mod url_ignore_glob;
+mod url_ignored;
+mod url_unignore;
+mod url_why;
+mod which;
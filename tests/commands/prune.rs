@@ -0,0 +1,302 @@
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+fn scan_then_remove_a_file(wints: &mut WintsCommand) -> Result<()> {
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+        "#,
+    )?;
+    wints.new_file_in_folder(
+        "folder",
+        "file2.txt",
+        r#"
+            Got to https://site2.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(folder.display().to_string());
+    wints.cmd.assert().success();
+
+    fs::remove_file(folder.join("file2.txt"))?;
+    wints.new_cmd()?;
+    Ok(())
+}
+
+#[test]
+fn scan_stale() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    scan_then_remove_a_file(&mut wints)?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--stale")
+        .arg(wints.current_dir.join("folder").display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " ⚠️ 'https://site2.dev/' is no longer found under",
+        ))
+        .stdout(contains("'https://site1.dev/' is no longer found").not())
+        .stdout(contains(
+            " ℹ️ 1 stale URL, use 'wints prune' to remove them.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn prune() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    scan_then_remove_a_file(&mut wints)?;
+
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(wints.current_dir.join("folder").display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🔎 Scanning for stale URLs..."))
+        .stdout(contains(" 📝 'https://site2.dev/' have been removed"))
+        .stdout(contains(" ✅ Prune completed."));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("modules/main.yaml"))?;
+    assert!(content.contains("https://site1.dev"));
+    assert!(!content.contains("https://site2.dev"));
+
+    Ok(())
+}
+
+#[test]
+fn prune_to_obsolete() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    scan_then_remove_a_file(&mut wints)?;
+
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--obsolete")
+        .arg(wints.current_dir.join("folder").display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'https://site2.dev/' have been moved to the context 'obsolete'",
+    ));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("modules/main.yaml"))?;
+    assert!(content.contains(
+        r#"- context: obsolete
+  urls:
+  - https://site2.dev/
+"#
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn prune_dry_run() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    scan_then_remove_a_file(&mut wints)?;
+
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg(wints.current_dir.join("folder").display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🌀 Dry-run mode activated."))
+        .stdout(contains(" 🌀 Remove 'https://site2.dev/'"));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("modules/main.yaml"))?;
+    assert!(content.contains("https://site2.dev"));
+
+    Ok(())
+}
+
+#[test]
+fn prune_nothing() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(wints.current_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 Prune found no stale urls."));
+
+    Ok(())
+}
+
+#[test]
+fn prune_from_a_subfolder() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    wints.new_file("file1.md", "Got to https://site1.dev")?;
+    let subfolder = wints.new_file_in_folder("sub", "file2.md", "Got to https://site2.dev")?;
+
+    wints
+        .cmd
+        .current_dir(&wints.current_dir)
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(".");
+    wints.cmd.assert().success();
+    let content = fs::read_to_string(wints.local_config_dir.join("modules").join("main.yaml"))?;
+    assert!(content.contains("sub/file2.md:1:8"));
+
+    fs::remove_file(subfolder.join("file2.md"))?;
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .current_dir(&subfolder)
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg(".");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🌀 Remove 'https://site2.dev/'"))
+        .stdout(contains("Remove 'https://site1.dev/'").not())
+        .stdout(contains("-  - sub/file2.md:1:8"));
+
+    Ok(())
+}
+
+#[test]
+fn prune_with_the_scan_file_selection() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    let folder = wints.new_file_in_folder("folder", "file1.md", "Got to https://site1.dev")?;
+    wints.new_file_in_folder("folder/.github", "file2.md", "Got to https://site2.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--hidden")
+        .arg(folder.display().to_string());
+    wints.cmd.assert().success();
+
+    // Hidden files are not scanned by prune, their URLs are kept
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 Prune found no stale urls."));
+
+    fs::write(folder.join(".github").join("file2.md"), "No more links\n")?;
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--hidden")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🌀 Remove 'https://site2.dev/'"))
+        .stdout(contains("Remove 'https://site1.dev/'").not());
+
+    Ok(())
+}
+
+#[test]
+fn prune_dry_run_json_alone_on_stdout() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+    scan_then_remove_a_file(&mut wints)?;
+
+    wints
+        .cmd
+        .arg("prune")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--json")
+        .arg(wints.current_dir.join("folder").display().to_string());
+
+    let output = wints.cmd.output()?;
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!("modified", document["changes"][0]["status"]);
+    assert!(String::from_utf8(output.stderr)?.contains(" 🌀 Remove 'https://site2.dev/'"));
+
+    Ok(())
+}
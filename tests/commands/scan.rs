@@ -1,3 +1,6 @@
+use std::path::Path;
+use std::process::Command;
+
 use anyhow::Result;
 use assert_cmd::prelude::*;
 use predicate::str::contains;
@@ -12,9 +15,9 @@ fn specific_path() -> Result<()> {
     wints.new_file(
         "file1.txt",
         r#"
-            Got to https://site1.tld
-            Got to https://site2.tld
-            Got to https://site3.tld
+            Got to https://site1.dev
+            Got to https://site2.dev
+            Got to https://site3.dev
         "#,
     )?;
 
@@ -22,9 +25,9 @@ fn specific_path() -> Result<()> {
         "folder",
         "file2.txt",
         r#"
-            Got to https://site4.tld
-            Got to https://site5.tld
-            Got to https://site6.tld
+            Got to https://site4.dev
+            Got to https://site5.dev
+            Got to https://site6.dev
         "#,
     )?;
 
@@ -77,18 +80,18 @@ fn using_dry_run() -> Result<()> {
     wints.new_file(
         "file1.txt",
         r#"
-            Got to https://site1.tld
-            Got to https://site2.tld
-            Got to https://site3.tld
+            Got to https://site1.dev
+            Got to https://site2.dev
+            Got to https://site3.dev
         "#,
     )?;
 
     wints.new_file(
         "file2.txt",
         r#"
-            Got to https://site4.tld
-            Got to https://site5.tld
-            Got to https://site6.tld
+            Got to https://site4.dev
+            Got to https://site5.dev
+            Got to https://site6.dev
         "#,
     )?;
 
@@ -142,9 +145,9 @@ fn using_another_module() -> Result<()> {
     wints.new_file(
         "file1.txt",
         r#"
-            Got to https://site1.tld
-            Got to https://site2.tld
-            Got to https://site3.tld
+            Got to https://site1.dev
+            Got to https://site2.dev
+            Got to https://site3.dev
         "#,
     )?;
 
@@ -178,9 +181,9 @@ fn using_another_global_module() -> Result<()> {
     wints.new_file(
         "file1.txt",
         r#"
-            Got to https://site1.tld
-            Got to https://site2.tld
-            Got to https://site3.tld
+            Got to https://site1.dev
+            Got to https://site2.dev
+            Got to https://site3.dev
         "#,
     )?;
 
@@ -207,3 +210,855 @@ fn using_another_global_module() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn using_infer_context() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file(
+        "README.md",
+        r#"
+# Deployment
+
+Read the [runbook](https://site1.dev)
+
+Got to https://site2.dev
+        "#,
+    )?;
+
+    wints.new_file(
+        "file1.txt",
+        r#"
+            Got to https://site3.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--infer-context")
+        .arg(wints.current_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🔎 Scanning for new URLs..."))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'deployment runbook', ready to be sorted.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'deployment', ready to be sorted.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ))
+        .stdout(contains(" ✅ Scan completed."));
+
+    Ok(())
+}
+
+#[test]
+fn using_report() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file(
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+            Got to https://site1.dev and https://site2.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--report")
+        .arg(wints.current_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site1.dev"))
+        .stdout(contains("file1.txt:2:20"))
+        .stdout(contains("file1.txt:3:20"))
+        .stdout(contains(" 📍 https://site2.dev"))
+        .stdout(contains("file1.txt:3:42"))
+        .stdout(contains(" 📝 2 URLs have been added in context 'need to be contextualised', ready to be sorted."));
+
+    let content = std::fs::read_to_string(wints.local_config_dir.join("modules/main.yaml"))?;
+    assert!(content.contains("sources:"));
+    assert!(content.contains("file1.txt:2:20"));
+
+    Ok(())
+}
+
+#[test]
+fn using_cache() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--cache")
+        .arg(folder.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " ℹ️ 0 unchanged files reused from the cache, 1 files scanned.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    assert!(wints.local_config_dir.join("cache/scan.json").exists());
+
+    wints.new_file_in_folder(
+        "folder",
+        "file2.txt",
+        r#"
+            Got to https://site2.dev
+        "#,
+    )?;
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--cache")
+        .arg(folder.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " ℹ️ 1 unchanged files reused from the cache, 1 files scanned.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_no_cache() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        "version: 1\nscan:\n  cache: true",
+    )?;
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--no-cache")
+        .arg(folder.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("from the cache").not())
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    assert!(!wints.local_config_dir.join("cache/scan.json").exists());
+
+    Ok(())
+}
+
+#[test]
+fn compare_canonical_urls() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to HTTPS://Site1.dev/?utm_source=mail
+            Got to https://site2.dev/, or https://site2.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site1.dev")
+        .arg("context");
+    wints.cmd.assert().success();
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(folder.display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+    ));
+
+    let content =
+        std::fs::read_to_string(wints.local_config_dir.join("modules").join("main.yaml"))?;
+    assert!(content.contains("  - https://site2.dev/\n"));
+
+    Ok(())
+}
+
+#[test]
+fn using_ignore_patterns() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+ignored_urls: []
+ignored_patterns:
+- host: "*.example.com"
+- prefix: https://github.com/org/repo/blob/
+- glob: http://localhost:*
+- regex: ^https?://internal\.
+- host: expired.dev
+  expires: 2000-01-01
+        "#,
+    )?;
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://www.example.com/page
+            Got to https://github.com/org/repo/blob/main/README.md
+            Got to http://localhost:8080/api
+            Got to http://internal.corp.dev/wiki
+            Got to https://expired.dev
+            Got to https://site1.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(folder.display().to_string());
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 2 URLs have been added in context 'need to be contextualised', ready to be sorted.",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn using_noise_filters() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://example.com and https://site.tld
+            Got to http://localhost:8080/api and http://192.168.1.10
+            Got to http://www.w3.org/2001/XMLSchema
+            Got to http://www.apache.org/licenses/LICENSE-2.0
+            Got to https://registry.npmjs.org/serde/-/serde-1.0.0.tgz
+            Got to https://site1.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(folder.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ Noise filter 'placeholder' dropped 2 URLs."))
+        .stdout(contains(
+            " ℹ️ Noise filter 'private-network' dropped 2 URLs.",
+        ))
+        .stdout(contains(" ℹ️ Noise filter 'xml-namespace' dropped 1 URL."))
+        .stdout(contains(" ℹ️ Noise filter 'license' dropped 1 URL."))
+        .stdout(contains(" ℹ️ Noise filter 'registry' dropped 1 URL."))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_disabled_noise_filters() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://example.com and https://site.tld
+            Got to http://localhost:8080/api
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--disable-noise-filter")
+        .arg("placeholder")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(
+            " ℹ️ Noise filter 'private-network' dropped 1 URL.",
+        ))
+        .stdout(contains("Append 2 URLs on the context"));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--no-noise-filter")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("Noise filter").not())
+        .stdout(contains("Append 3 URLs on the context"));
+
+    Ok(())
+}
+
+#[test]
+fn using_module_ignore_rules() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+ignored_urls:
+- https://site1.dev
+        "#,
+    )?;
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "docs.yaml",
+        r#"
+version: 1
+elements: []
+ignored_patterns:
+- host: "*.internal.dev"
+ignored_globs:
+- drafts/
+        "#,
+    )?;
+    wints.new_file_in_folder("local_config_dir", "ignore.docs", "*.log")?;
+    let folder = wints.new_file_in_folder(
+        "folder",
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+            Got to https://wiki.internal.dev
+            Got to https://site2.dev
+        "#,
+    )?;
+    wints.new_file_in_folder("folder/drafts", "file2.txt", "Got to https://site3.dev")?;
+    wints.new_file_in_folder("folder", "file3.log", "Got to https://site4.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--module")
+        .arg("docs")
+        .arg("--dry-run")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("Append 1 URL on the context"));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg(folder.display().to_string());
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("Append 4 URLs on the context"));
+
+    Ok(())
+}
+
+#[test]
+fn using_file_selection() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+scan:
+  max_depth: 1
+        "#,
+    )?;
+    let docs = wints.new_file_in_folder("docs", "guide.md", "Got to https://site1.dev")?;
+    wints.new_file_in_folder("docs/deep", "other.md", "Got to https://site2.dev")?;
+    let notes = wints.new_file_in_folder("notes", "todo.txt", "Got to https://site3.dev")?;
+    wints.new_file_in_folder("notes", "todo.md", "Got to https://site4.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--type")
+        .arg("md")
+        .arg("--report")
+        .arg(docs.display().to_string())
+        .arg(notes.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site1.dev"))
+        .stdout(contains(" 📍 https://site4.dev"))
+        .stdout(contains(
+            " 📝 2 URLs have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_comments_only() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let sources = wints.new_file_in_folder(
+        "sources",
+        "main.rs",
+        r#"// See https://site1.dev
+let api = "https://site2.dev";"#,
+    )?;
+    wints.new_file_in_folder("sources", "README.md", "Got to https://site3.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--comments-only")
+        .arg("--report")
+        .arg(sources.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site1.dev"))
+        .stdout(contains(" 📍 https://site3.dev"))
+        .stdout(contains(
+            " 📝 2 URLs have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_html_links() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let site = wints.new_file_in_folder(
+        "site",
+        "index.html",
+        r#"<a href="https://site1.dev/?a=1&amp;b=2">Home</a> <a href="docs/intro.html">Intro</a>"#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--site-root")
+        .arg("https://site2.dev/v1")
+        .arg("--report")
+        .arg(site.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site1.dev/?a=1&b=2"))
+        .stdout(contains(" 📍 https://site2.dev/v1/docs/intro.html"))
+        .stdout(contains(
+            " 📝 2 URLs have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_invalid_site_root() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--site-root")
+        .arg("docs");
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("invalid site root 'docs'"));
+
+    Ok(())
+}
+
+#[test]
+fn using_notebooks() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let notebooks = wints.new_file_in_folder(
+        "notebooks",
+        "analysis.ipynb",
+        r##"{"cells": [{"cell_type": "markdown", "source": ["# Data\n", "From [the lake](https://site1.dev/lake)"]}]}"##,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--report")
+        .arg(notebooks.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site1.dev/lake"))
+        .stdout(contains("analysis.ipynb:2:"))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_manifests() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let project = wints.new_file_in_folder(
+        "project",
+        "Cargo.toml",
+        r#"[package]
+name = "demo"
+repository = "https://github.com/someone/demo"
+
+[dependencies]
+serde = "1.0"
+"#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--manifests")
+        .arg(project.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("Cargo.toml'..."))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'crate page', ready to be sorted.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'crate docs', ready to be sorted.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'crate repository', ready to be sorted.",
+        ))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'dependency serde docs', ready to be sorted.",
+        ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("dependency serde docs");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains("https://docs.rs/serde"));
+
+    Ok(())
+}
+
+fn git(repository: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(repository)
+        .args(["-c", "user.name=wints", "-c", "user.email=wints@wints.dev"])
+        .args(args)
+        .output()?
+        .status;
+    assert!(status.success());
+    Ok(())
+}
+
+#[test]
+fn using_git_log() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let repository =
+        wints.new_file_in_folder("repository", "design.md", "See https://site1.dev")?;
+    git(&repository, &["init", "--quiet"])?;
+    git(&repository, &["add", "design.md"])?;
+    git(
+        &repository,
+        &[
+            "commit",
+            "--quiet",
+            "-m",
+            "Add design\n\nIncident https://site2.dev/42",
+        ],
+    )?;
+    git(&repository, &["rm", "--quiet", "design.md"])?;
+    git(&repository, &["commit", "--quiet", "-m", "Remove design"])?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--git-log")
+        .arg("--report")
+        .arg(repository.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 https://site2.dev/42"))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--git-log")
+        .arg("--git-diffs")
+        .arg("--stale")
+        .arg(repository.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ Scan found no stale urls."))
+        .stdout(contains(
+            " 📝 1 URL have been added in context 'need to be contextualised', ready to be sorted.",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn using_git_log_outside_of_a_repository() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder("folder", "README.md", "See https://site1.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--git-log")
+        .arg(folder.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("is not in a git repository"));
+
+    Ok(())
+}
+
+#[test]
+fn using_unknown_file_type() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--type")
+        .arg("not-a-type")
+        .arg(wints.current_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("invalid file type"));
+
+    Ok(())
+}
+
+#[test]
+fn dry_run_json_alone_on_stdout() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let folder = wints.new_file_in_folder("folder", "file1.txt", "Got to https://site1.dev")?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("--json")
+        .arg(folder.display().to_string());
+
+    let output = wints.cmd.output()?;
+    assert!(output.status.success());
+    let document: serde_json::Value = serde_json::from_slice(&output.stdout)?;
+    assert_eq!(serde_json::Value::Bool(true), document["dry_run"]);
+    assert_eq!("created", document["changes"][0]["status"]);
+    assert!(String::from_utf8(output.stderr)?.contains(" ✅ Scan completed."));
+
+    Ok(())
+}
@@ -201,3 +201,40 @@ fn all_words_are_unmatched() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn open_same_url_once() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "main.yaml",
+        r#"
+version: 1
+elements:
+- context: some docs
+  urls:
+  - https://site.tld
+- context: other docs
+  urls:
+  - https://site.tld/?utm_source=mail
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run")
+        .arg("docs");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🌀 Open https://site.tld").count(1));
+
+    Ok(())
+}
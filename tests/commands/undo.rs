@@ -0,0 +1,227 @@
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn undo_and_redo_an_add() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let main_module = wints
+        .new_file_in_folder(
+            "local_config_dir/modules",
+            "main.yaml",
+            "version: 1\nelements: []",
+        )?
+        .join("main.yaml");
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld")
+        .arg("context");
+
+    wints.cmd.assert().success();
+    assert!(fs::read_to_string(&main_module)?.contains("https://site.tld"));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("log")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 1 journal entries:"))
+        .stdout(contains("add --config"))
+        .stdout(contains("https://site.tld context (1 files)"))
+        .stdout(contains(format!(
+            "        modified '{}'",
+            main_module.display()
+        )));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("undo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📝 Undo #1 'wints add"))
+        .stdout(contains(" 📝 1 files have been restored as before #1"));
+    assert_eq!(
+        "version: 1\nelements: []\n",
+        fs::read_to_string(&main_module)?
+    );
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("undo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 Nothing to undo."));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("redo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--dry-run");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📝 Redo #1 'wints add"))
+        .stdout(contains("+  - https://site.tld"));
+    assert_eq!(
+        "version: 1\nelements: []\n",
+        fs::read_to_string(&main_module)?
+    );
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("redo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📝 1 files have been restored as after #1"));
+    assert!(fs::read_to_string(&main_module)?.contains("https://site.tld"));
+
+    Ok(())
+}
+
+#[test]
+fn undo_an_init_over_an_existing_module() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    let main_module = wints
+        .new_file_in_folder(
+            "local_config_dir/modules",
+            "main.yaml",
+            "version: 1\nelements:\n- context: docs\n  urls:\n  - https://docs.rs",
+        )?
+        .join("main.yaml");
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--force");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 A new 'main' module have been created with 0 contexts and 0 URLs",
+    ));
+    assert!(!fs::read_to_string(&main_module)?.contains("https://docs.rs"));
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("undo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints.cmd.assert().success();
+    assert!(fs::read_to_string(&main_module)?.contains("https://docs.rs"));
+
+    Ok(())
+}
+
+#[test]
+fn undo_a_file_changed_since() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("init")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints.cmd.assert().success();
+
+    let main_module = wints.local_config_dir.join("modules").join("main.yaml");
+    fs::write(&main_module, "version: 1\nelements: []\n# edited\n")?;
+
+    wints.new_cmd()?;
+    wints
+        .cmd
+        .arg("undo")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints.cmd.assert().failure().stderr(contains(format!(
+        "'{}' has been changed since the journal entry #1, it cannot be undone",
+        main_module.display()
+    )));
+    assert!(main_module.exists());
+
+    Ok(())
+}
+
+#[test]
+fn log_without_journal() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("log")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 The journal is empty."))
+        .stdout(contains("#").not());
+
+    Ok(())
+}
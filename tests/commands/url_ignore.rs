@@ -27,7 +27,7 @@ fn ignore_locally() -> Result<()> {
         .success()
         .stdout(contains(" 📝 Add 'https://site.tld' to the ignore list..."))
         .stdout(contains(
-            " 📝 'https://site.tld' have been added to the ignore list",
+            " 📝 'https://site.tld/' have been added to the ignore list",
         ));
 
     let config_path = wints.local_config_dir.join("options.yaml");
@@ -37,7 +37,7 @@ fn ignore_locally() -> Result<()> {
         content,
         r#"version: 1
 ignored_urls:
-- https://site.tld
+- https://site.tld/
 "#
     );
 
@@ -67,9 +67,11 @@ fn ignore_globally() -> Result<()> {
             " 📝 Add 'https://site.tld' to the global ignore list...",
         ))
         .stdout(contains(
-            " 📝 'https://site.tld' have been added to the global ignore list",
+            " 📝 'https://site.tld/' have been added to the global ignore list",
         ));
 
+    // The local configuration folder is left untouched
+    assert!(!wints.local_config_dir.exists());
     let config_path = wints.home_dir_config_dir.join("options.yaml");
     assert!(config_path.exists());
     let content = fs::read_to_string(config_path)?;
@@ -77,7 +79,7 @@ fn ignore_globally() -> Result<()> {
         content,
         r#"version: 1
 ignored_urls:
-- https://site.tld
+- https://site.tld/
 "#
     );
 
@@ -104,7 +106,7 @@ fn multiple_ignore_locally() -> Result<()> {
         .success()
         .stdout(contains(" 📝 Add 'https://site.tld' to the ignore list..."))
         .stdout(contains(
-            " 📝 'https://site.tld' have been added to the ignore list",
+            " 📝 'https://site.tld/' have been added to the ignore list",
         ));
 
     wints.new_cmd()?;
@@ -127,7 +129,7 @@ fn multiple_ignore_locally() -> Result<()> {
             " 📝 Add 'https://site2.tld' to the ignore list...",
         ))
         .stdout(contains(
-            " 📝 'https://site2.tld' have been added to the ignore list",
+            " 📝 'https://site2.tld/' have been added to the ignore list",
         ));
 
     let config_path = wints.local_config_dir.join("options.yaml");
@@ -137,8 +139,8 @@ fn multiple_ignore_locally() -> Result<()> {
         content,
         r#"version: 1
 ignored_urls:
-- https://site.tld
-- https://site2.tld
+- https://site.tld/
+- https://site2.tld/
 "#
     );
 
@@ -168,7 +170,7 @@ fn multiple_ignore_globally() -> Result<()> {
             " 📝 Add 'https://site.tld' to the global ignore list...",
         ))
         .stdout(contains(
-            " 📝 'https://site.tld' have been added to the global ignore list",
+            " 📝 'https://site.tld/' have been added to the global ignore list",
         ));
 
     wints.new_cmd()?;
@@ -192,7 +194,7 @@ fn multiple_ignore_globally() -> Result<()> {
             " 📝 Add 'https://site2.tld' to the global ignore list...",
         ))
         .stdout(contains(
-            " 📝 'https://site2.tld' have been added to the global ignore list",
+            " 📝 'https://site2.tld/' have been added to the global ignore list",
         ));
 
     let config_path = wints.home_dir_config_dir.join("options.yaml");
@@ -202,8 +204,8 @@ fn multiple_ignore_globally() -> Result<()> {
         content,
         r#"version: 1
 ignored_urls:
-- https://site.tld
-- https://site2.tld
+- https://site.tld/
+- https://site2.tld/
 "#
     );
 
@@ -230,7 +232,126 @@ fn ignore_dry_run() -> Result<()> {
         .assert()
         .success()
         .stdout(contains(" 📝 Add 'https://site.tld' to the ignore list..."))
-        .stdout(contains(" 🌀 Add 'https://site.tld' to the ignore list"));
+        .stdout(contains(" 🌀 Add 'https://site.tld/' to the ignore list"));
+
+    Ok(())
+}
+
+#[test]
+fn ignore_canonical_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://Site.tld/");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'https://site.tld/' have been added to the ignore list",
+    ));
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.tld?utm_medium=email");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 'https://site.tld/' is already ignored"));
+
+    Ok(())
+}
+
+#[test]
+fn ignore_patterns() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--pattern")
+        .arg("--reason")
+        .arg("placeholders")
+        .arg("*.example.com");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 '*.example.com' have been added to the ignore list",
+    ));
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--regex")
+        .arg("--expires")
+        .arg("2999-12-31")
+        .arg("^http://localhost");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 '^http://localhost' have been added to the ignore list",
+    ));
+
+    let config_path = wints.local_config_dir.join("options.yaml");
+    let content = fs::read_to_string(config_path)?;
+    assert_eq!(
+        content,
+        r#"version: 1
+ignored_patterns:
+- host: '*.example.com'
+  reason: placeholders
+- regex: ^http://localhost
+  expires: 2999-12-31
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn ignore_invalid_pattern() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--regex")
+        .arg("(https://");
+
+    wints
+        .cmd
+        .assert()
+        .failure()
+        .stderr(contains("invalid regex '(https://'"));
 
     Ok(())
 }
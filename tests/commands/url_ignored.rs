@@ -0,0 +1,113 @@
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn list_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+ignored_urls:
+- https://site.tld
+ignored_patterns:
+- host: "*.corp.tld"
+  reason: internal
+  expires: 2999-12-31
+        "#,
+    )?;
+    wints.new_file_in_folder("local_config_dir", "ignore", "target/\n")?;
+    wints.new_file_in_folder("home_dir_config_dir", "ignore", "# vendored\nvendor/\n")?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignored")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 4 ignore entries:"))
+        .stdout(contains("[local] url 'https://site.tld'"))
+        .stdout(contains(
+            "[local] host '*.corp.tld', internal (until 2999-12-31)",
+        ))
+        .stdout(contains("[local] path 'target/'"))
+        .stdout(contains("[global] path 'vendor/'"));
+
+    Ok(())
+}
+
+#[test]
+fn list_nothing_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignored")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string());
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 No ignored URLs or globs."));
+
+    Ok(())
+}
+
+#[test]
+fn list_module_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir/modules",
+        "docs.yaml",
+        r#"
+version: 1
+elements: []
+ignored_urls:
+- https://site.tld
+ignored_globs:
+- drafts/
+        "#,
+    )?;
+    wints.new_file_in_folder("local_config_dir", "ignore.docs", "*.log")?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("ignored")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--module")
+        .arg("docs");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 3 ignore entries:"))
+        .stdout(contains("[module docs] url 'https://site.tld'"))
+        .stdout(contains("[module docs] path 'drafts/'"))
+        .stdout(contains("[module docs] path '*.log'"));
+
+    Ok(())
+}
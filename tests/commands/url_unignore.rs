@@ -0,0 +1,115 @@
+use std::fs;
+
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn unignore_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "local_config_dir",
+        "options.yaml",
+        r#"# Shared ignore list
+version: 1
+ignored_urls:
+- https://site.tld
+- https://other.tld # staging
+ignored_patterns:
+- host: "*.corp.tld""#,
+    )?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("unignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://SITE.tld/");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'https://SITE.tld/' have been removed from the ignore list",
+    ));
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("unignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("*.corp.tld");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 '*.corp.tld' have been removed from the ignore list",
+    ));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("options.yaml"))?;
+    assert_eq!(
+        content,
+        r#"# Shared ignore list
+version: 1
+ignored_urls:
+- https://other.tld # staging
+"#
+    );
+
+    Ok(())
+}
+
+#[test]
+fn unignore_unknown_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("unignore")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("--global")
+        .arg("https://site.tld");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 😢 'https://site.tld' is not in the global ignore list",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn unignore_glob() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder("local_config_dir", "ignore", "target/\nvendor/")?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("unignore-glob")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("target/");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📝 'target/' have been removed from the ignore list",
+    ));
+
+    let content = fs::read_to_string(wints.local_config_dir.join("ignore"))?;
+    assert_eq!(content, "vendor/\n");
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn why_url_is_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder(
+        "home_dir_config_dir",
+        "options.yaml",
+        r#"
+version: 1
+ignored_patterns:
+- host: "*.corp.dev"
+  reason: internal wiki
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("why")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://wiki.corp.dev/page");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 Ignored by host '*.corp.dev' in '"))
+        .stdout(contains(
+            "home_dir_config_dir/options.yaml' (global): internal wiki",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn why_url_is_noise() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("why")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("http://localhost:8080");
+
+    wints.cmd.assert().success().stdout(contains(
+        " 📍 Dropped by the noise filter 'private-network'",
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn why_path_is_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder("local_config_dir", "ignore", "target/\n*.log")?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("why")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("target/debug/build.txt");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 📍 Ignored by path 'target/' in '"))
+        .stdout(contains("local_config_dir/ignore'"));
+
+    Ok(())
+}
+
+#[test]
+fn why_path_is_ignored_by_an_anchored_glob() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file_in_folder("local_config_dir", "ignore", "/docs/drafts")?;
+    let docs = wints.new_file_in_folder("docs/drafts", "draft.md", "https://draft.dev")?;
+
+    for (current_dir, target) in [
+        (
+            wints.current_dir.join("docs"),
+            "drafts/draft.md".to_string(),
+        ),
+        (
+            wints.current_dir.clone(),
+            docs.join("draft.md").display().to_string(),
+        ),
+    ] {
+        wints.new_cmd()?;
+        wints
+            .cmd
+            .current_dir(current_dir)
+            .arg("url")
+            .arg("why")
+            .arg("--config")
+            .arg(wints.local_config_dir.display().to_string())
+            .arg("--global-config")
+            .arg(wints.home_dir_config_dir.display().to_string())
+            .arg(target);
+
+        wints
+            .cmd
+            .assert()
+            .success()
+            .stdout(contains(" 📍 Ignored by path '/docs/drafts' in '"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn why_not_ignored() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("url")
+        .arg("why")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://github.com/rlespinasse/wints");
+
+    wints.cmd.assert().success().stdout(contains(
+        " ℹ️ 'https://github.com/rlespinasse/wints' is not ignored",
+    ));
+
+    Ok(())
+}
@@ -0,0 +1,128 @@
+use anyhow::Result;
+use assert_cmd::prelude::*;
+use predicate::str::contains;
+use predicates::prelude::*;
+
+use crate::WintsCommand;
+
+#[test]
+fn which_scanned_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints.new_file(
+        "file1.txt",
+        r#"
+            Got to https://site1.dev
+        "#,
+    )?;
+
+    wints
+        .cmd
+        .arg("scan")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg(wints.current_dir.display().to_string());
+
+    wints.cmd.assert().success();
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("which")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site1.dev");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 🔎 Looking for 'https://site1.dev'..."))
+        .stdout(contains(
+            " ℹ️ 'https://site1.dev' is in context 'need to be contextualised'",
+        ))
+        .stdout(contains(" 📍 Found in "))
+        .stdout(contains("file1.txt:2:20"));
+
+    Ok(())
+}
+
+#[test]
+fn which_added_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.dev")
+        .arg("context");
+
+    wints.cmd.assert().success();
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("which")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.dev");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" ℹ️ 'https://site.dev' is in context 'context'"))
+        .stdout(contains(
+            " ℹ️ No scan source recorded for 'https://site.dev'",
+        ));
+
+    Ok(())
+}
+
+#[test]
+fn which_unknown_url() -> Result<()> {
+    let mut wints = WintsCommand::new()?;
+
+    wints
+        .cmd
+        .arg("add")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://site.dev")
+        .arg("context");
+
+    wints.cmd.assert().success();
+
+    wints.new_cmd()?;
+
+    wints
+        .cmd
+        .arg("which")
+        .arg("--config")
+        .arg(wints.local_config_dir.display().to_string())
+        .arg("--global-config")
+        .arg(wints.home_dir_config_dir.display().to_string())
+        .arg("https://other.dev");
+
+    wints
+        .cmd
+        .assert()
+        .success()
+        .stdout(contains(" 😢 'https://other.dev' is not part of 'main'."));
+
+    Ok(())
+}